@@ -0,0 +1,32 @@
+//! Command line options shared by all binaries, consumed so that positional arguments remain.
+
+/// Removes every `flag` from `args`, returns whether there was any.
+pub fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let len = args.len();
+    args.retain(|arg| arg != flag);
+    args.len() != len
+}
+
+/// Removes `name <value>` from `args` and returns the value.
+/// Panics if `name` is the last argument.
+pub fn take_arg(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let idx = args.iter().position(|arg| arg == name)?;
+    args.remove(idx);
+    assert!(idx < args.len(), "{name} requires a value");
+    Some(args.remove(idx))
+}
+
+#[test]
+fn test() {
+    let mut args = ["1", "--wrap", "--top", "3", "--wrap", "2"]
+        .map(String::from)
+        .to_vec();
+    assert!(take_flag(&mut args, "--wrap"));
+    assert!(!take_flag(&mut args, "--wrap"));
+    assert_eq!(take_arg(&mut args, "--top").as_deref(), Some("3"));
+    assert_eq!(take_arg(&mut args, "--top"), None);
+    assert_eq!(args, ["1", "2"]);
+
+    let result = std::panic::catch_unwind(|| take_arg(&mut vec![String::from("--top")], "--top"));
+    assert!(result.is_err());
+}
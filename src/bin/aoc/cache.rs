@@ -0,0 +1,113 @@
+use crate::solutions::Solution;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+pub(crate) struct Cache {
+    dir: PathBuf,
+    /// Hash of the running executable, so answers from an older build are never reused.
+    /// `None` when the executable can't be read, every lookup is a miss then.
+    build: Option<String>,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new(concat!(env!("CARGO_MANIFEST_DIR"), "/cache"))
+    }
+}
+
+fn sha256(data: impl AsRef<[u8]>) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+impl Cache {
+    pub(crate) fn new(dir: impl Into<PathBuf>) -> Self {
+        let build = std::env::current_exe()
+            .and_then(std::fs::read)
+            .ok()
+            .map(sha256);
+        Self {
+            dir: dir.into(),
+            build,
+        }
+    }
+
+    fn path(&self, solution: &Solution, input: &str) -> Option<PathBuf> {
+        let build = self.build.as_ref()?;
+        Some(self.dir.join(format!(
+            "day{}-part{}-{}-{}",
+            solution.day,
+            solution.part,
+            sha256(input),
+            build
+        )))
+    }
+
+    pub(crate) fn read(&self, solution: &Solution, input: &str) -> Option<String> {
+        std::fs::read_to_string(self.path(solution, input)?).ok()
+    }
+
+    /// Stores an answer, failing to do so only costs a cache miss next time.
+    pub(crate) fn write(&self, solution: &Solution, input: &str, answer: &str) {
+        let Some(path) = self.path(solution, input) else {
+            return;
+        };
+        let written =
+            std::fs::create_dir_all(&self.dir).and_then(|_| std::fs::write(&path, answer));
+        if let Err(err) = written {
+            eprintln!("can't write {}: {err}", path.display());
+        }
+    }
+
+    /// Returns a cached answer and `true`, or solves, stores the answer and returns `false`.
+    pub(crate) fn fetch(&self, solution: &Solution, input: &str) -> (String, bool) {
+        if let Some(answer) = self.read(solution, input) {
+            (answer, true)
+        } else {
+            let answer = (solution.solve)(input);
+            self.write(solution, input, &answer);
+            (answer, false)
+        }
+    }
+
+    pub(crate) fn clear(&self) -> std::io::Result<()> {
+        match std::fs::remove_dir_all(&self.dir) {
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+}
+
+#[test]
+fn test() {
+    let dir = std::env::temp_dir().join(format!("aoc-cache-test-{}", std::process::id()));
+    let cache = Cache::new(&dir);
+    let solution = Solution::find(1, 1).unwrap();
+    let input = include_str!("../day1/input1_test.txt");
+
+    assert_eq!(cache.fetch(solution, input), ("11".to_string(), false));
+    assert_eq!(cache.fetch(solution, input), ("11".to_string(), true));
+    assert_eq!(cache.fetch(solution, "1   1\n"), ("0".to_string(), false));
+
+    cache.clear().unwrap();
+    assert_eq!(cache.fetch(solution, input), ("11".to_string(), false));
+    cache.clear().unwrap();
+    cache.clear().unwrap();
+
+    // answers from a different build are misses
+    let other = Cache {
+        build: Some(sha256("other build")),
+        ..Cache::new(&dir)
+    };
+    assert_eq!(other.fetch(solution, input), ("11".to_string(), false));
+
+    // an unwritable cache still answers
+    let file = dir.join("file");
+    std::fs::write(&file, "").unwrap();
+    let broken = Cache::new(file.join("cache"));
+    assert_eq!(broken.fetch(solution, input), ("11".to_string(), false));
+    assert_eq!(broken.fetch(solution, input), ("11".to_string(), false));
+    cache.clear().unwrap();
+}
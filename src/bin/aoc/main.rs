@@ -0,0 +1,132 @@
+mod cache;
+mod server;
+mod solutions;
+
+use adventofcode2024::{
+    allocator::Counting,
+    args::{take_arg, take_flag},
+};
+use cache::Cache;
+use server::Server;
+use solutions::{Solution, SOLUTIONS};
+use std::time::Instant;
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+const USAGE: &str = "\
+usage:
+    aoc [--no-cache] [<day> [<part>]]
+    aoc [--no-cache] serve [--port <port>]
+    aoc repl <day>
+    aoc inspect <day>
+    aoc cache clear";
+
+fn main() {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let no_cache = take_flag(&mut args, "--no-cache");
+    let port = take_arg(&mut args, "--port");
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match args.as_slice() {
+        ["cache", "clear"] => Cache::default()
+            .clear()
+            .unwrap_or_else(|err| exit_with(&format!("can't clear cache: {err}"))),
+        ["serve"] => serve(port.as_deref().map_or(8080, parse_port), no_cache),
+        ["repl", day] => repl(parse_number(day)),
+        ["inspect", day] => inspect(parse_number(day)),
+        [] => run(SOLUTIONS.iter(), no_cache),
+        [day] => run(Solution::of_day(parse_number(day)), no_cache),
+        [day, part] => {
+            let (day, part) = (parse_number(day), parse_number(part));
+            let Some(solution) = Solution::find(day, part) else {
+                exit_with(&format!("no solution for day {day} part {part}"));
+            };
+            run(std::iter::once(solution), no_cache)
+        }
+        _ => exit_with(USAGE),
+    }
+}
+
+fn parse_port(arg: &str) -> u16 {
+    arg.parse()
+        .unwrap_or_else(|_| exit_with(&format!("expected a port, got {arg:?}\n{USAGE}")))
+}
+
+fn parse_number(arg: &str) -> u8 {
+    arg.parse()
+        .unwrap_or_else(|_| exit_with(&format!("expected a number, got {arg:?}\n{USAGE}")))
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{message}");
+    std::process::exit(1)
+}
+
+fn run(solutions: impl Iterator<Item = &'static Solution>, no_cache: bool) {
+    let cache = Cache::default();
+
+    for solution in solutions {
+        let input = match std::fs::read_to_string(solution.input_path()) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("{solution}: can't read {}: {err}", solution.input_path());
+                continue;
+            }
+        };
+
+        if !no_cache {
+            if let Some(answer) = cache.read(solution, &input) {
+                println!("{solution}: {answer} (cached)");
+                continue;
+            }
+        }
+
+        let baseline = Counting::reset();
+        let started_at = Instant::now();
+        let answer = (solution.solve)(&input);
+        let elapsed = started_at.elapsed();
+        let stats = baseline.stats();
+
+        if !no_cache {
+            cache.write(solution, &input, &answer);
+        }
+
+        println!("{solution}: {answer} ({elapsed:?}, {stats})");
+    }
+}
+
+fn serve(port: u16, no_cache: bool) {
+    let server = Server::bind(port, Cache::default(), no_cache)
+        .unwrap_or_else(|err| exit_with(&format!("can't bind to port {port}: {err}")));
+    println!("listening on http://{}", server.local_addr());
+    server.serve()
+}
+
+fn repl(day: u8) {
+    let Some(repl) = solutions::find_repl(day) else {
+        exit_with(&format!("no repl for day {day}"));
+    };
+    let path = solutions::input_path(day);
+    let input = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| exit_with(&format!("can't read {path}: {err}")));
+
+    let mut state = repl(&input);
+    let stdin = std::io::stdin();
+    adventofcode2024::repl::run(&mut *state, stdin.lock(), std::io::stdout()).unwrap()
+}
+
+fn inspect(day: u8) {
+    let Some(inspect) = solutions::find_inspect(day) else {
+        exit_with(&format!("no inspect for day {day}"));
+    };
+    let path = solutions::input_path(day);
+    let input = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| exit_with(&format!("can't read {path}: {err}")));
+
+    let report = inspect(&input);
+    print!("{report}");
+    if !report.passed() {
+        exit_with(&format!("day {day}: input violates solver assumptions"));
+    }
+}
@@ -0,0 +1,334 @@
+use crate::{
+    cache::Cache,
+    solutions::{Solution, SOLUTIONS},
+};
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    panic::AssertUnwindSafe,
+    time::{Duration, Instant},
+};
+
+/// Largest request body accepted, puzzle inputs are far smaller.
+const MAX_BODY_LEN: usize = 1024 * 1024;
+/// Largest request line and headers accepted, all together.
+const MAX_HEADERS_LEN: u64 = 8 * 1024;
+/// How long a client can keep the server waiting for the rest of its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub(crate) struct Server {
+    listener: TcpListener,
+    cache: Cache,
+    no_cache: bool,
+    read_timeout: Duration,
+}
+
+struct Request {
+    method: String,
+    path: String,
+    body: String,
+}
+
+#[derive(Debug, PartialEq)]
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: format!("{{\"error\":{}}}", json_string(message)),
+        }
+    }
+
+    /// Reading a request failed, either the client is too slow or it sent garbage.
+    fn read_error(err: std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => {
+                Self::error(408, "timed out reading the request")
+            }
+            _ => Self::error(400, &format!("can't read the request: {err}")),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            413 => "Payload Too Large",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl Server {
+    pub(crate) fn bind(port: u16, cache: Cache, no_cache: bool) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        Ok(Self {
+            listener,
+            cache,
+            no_cache,
+            read_timeout: READ_TIMEOUT,
+        })
+    }
+
+    pub(crate) fn local_addr(&self) -> SocketAddr {
+        self.listener.local_addr().unwrap()
+    }
+
+    pub(crate) fn serve(&self) {
+        for stream in self.listener.incoming() {
+            let result = stream.and_then(|stream| self.handle(stream));
+            if let Err(err) = result {
+                eprintln!("connection failed: {err}");
+            }
+        }
+    }
+
+    fn handle(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(self.read_timeout))?;
+        let response = match Self::read_request(&stream) {
+            Ok(request) => self.route(&request.method, &request.path, &request.body),
+            Err(response) => response,
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            response.status,
+            response.reason(),
+            response.body.len(),
+            response.body
+        )?;
+        stream.flush()
+    }
+
+    fn read_request(stream: &TcpStream) -> Result<Request, Response> {
+        let mut reader = BufReader::new(stream.take(MAX_HEADERS_LEN));
+        let read_line = |reader: &mut BufReader<std::io::Take<&TcpStream>>| {
+            let mut line = String::new();
+            reader.read_line(&mut line).map_err(Response::read_error)?;
+            if !line.ends_with('\n') {
+                return Err(if reader.get_ref().limit() == 0 {
+                    Response::error(400, "request headers are too long")
+                } else {
+                    Response::error(400, "request ends in the middle of headers")
+                });
+            }
+            Ok(line)
+        };
+
+        let request_line = read_line(&mut reader)?;
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let path = parts.next().unwrap_or_default().to_string();
+
+        let mut content_length = Some(0);
+        loop {
+            let header = read_line(&mut reader)?;
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().ok();
+                }
+            }
+        }
+
+        let len = match content_length {
+            None => return Err(Response::error(400, "invalid Content-Length")),
+            Some(len) if len > MAX_BODY_LEN => {
+                return Err(Response::error(
+                    413,
+                    &format!("body is larger than {MAX_BODY_LEN} bytes"),
+                ))
+            }
+            Some(len) => len,
+        };
+        // part of the body may already be buffered with the headers
+        let buffered = reader.buffer().len();
+        reader
+            .get_mut()
+            .set_limit(len.saturating_sub(buffered) as u64);
+        let mut body = vec![0; len];
+        reader.read_exact(&mut body).map_err(Response::read_error)?;
+
+        let body =
+            String::from_utf8(body).map_err(|_| Response::error(400, "body is not valid UTF-8"))?;
+        Ok(Request { method, path, body })
+    }
+
+    fn route(&self, method: &str, path: &str, body: &str) -> Response {
+        let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+        match (method, segments.as_slice()) {
+            ("GET", ["health"]) => Response::ok(String::from("{\"status\":\"ok\"}")),
+            ("GET", ["list"]) => Response::ok(Self::list()),
+            ("POST", ["solve", day, part]) => {
+                let (Ok(day), Ok(part)) = (day.parse::<u8>(), part.parse::<u8>()) else {
+                    return Response::error(400, "day and part must be numbers");
+                };
+                let Some(solution) = Solution::find(day, part) else {
+                    return Response::error(404, &format!("no solution for day {day} part {part}"));
+                };
+                self.solve(solution, body)
+            }
+            (_, ["health"] | ["list"] | ["solve", _, _]) => {
+                Response::error(405, "method not allowed")
+            }
+            _ => Response::error(404, "not found"),
+        }
+    }
+
+    fn list() -> String {
+        let entries = SOLUTIONS
+            .iter()
+            .map(|s| format!("{{\"day\":{},\"part\":{}}}", s.day, s.part))
+            .collect::<Vec<_>>();
+        format!("[{}]", entries.join(","))
+    }
+
+    /// Solvers panic on malformed input, that's reported as a bad request
+    /// instead of taking the server down.
+    fn solve(&self, solution: &Solution, input: &str) -> Response {
+        let started_at = Instant::now();
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            if self.no_cache {
+                ((solution.solve)(input), false)
+            } else {
+                self.cache.fetch(solution, input)
+            }
+        }));
+        let elapsed = started_at.elapsed();
+
+        let (answer, cached) = match result {
+            Ok(result) => result,
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| String::from("solver panicked"));
+                return Response::error(400, &format!("can't solve: {message}"));
+            }
+        };
+
+        Response::ok(format!(
+            "{{\"day\":{},\"part\":{},\"answer\":{},\"cached\":{},\"elapsed_us\":{}}}",
+            solution.day,
+            solution.part,
+            json_string(&answer),
+            cached,
+            elapsed.as_micros()
+        ))
+    }
+}
+
+#[test]
+fn test() {
+    fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    fn raw_request(addr: SocketAddr, request: &[u8]) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    let mut server = Server::bind(0, Cache::default(), true).unwrap();
+    server.read_timeout = Duration::from_millis(200);
+    let addr = server.local_addr();
+    std::thread::spawn(move || server.serve());
+
+    let response = request(addr, "GET", "/health", "");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.ends_with("\r\n\r\n{\"status\":\"ok\"}"));
+
+    let response = request(addr, "GET", "/list", "");
+    assert!(response.contains("[{\"day\":1,\"part\":1},{\"day\":1,\"part\":2},"));
+
+    let input = include_str!("../day1/input1_test.txt");
+    let response = request(addr, "POST", "/solve/1/1", input);
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("{\"day\":1,\"part\":1,\"answer\":\"11\",\"cached\":false,"));
+
+    let response = request(addr, "POST", "/solve/14/2", input);
+    assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+    let response = request(addr, "GET", "/solve/1/1", "");
+    assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+
+    let response = request(addr, "POST", "/solve/6/1", "not a map\n");
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert!(response.contains("can't solve: "));
+    let response = request(addr, "GET", "/health", "");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+
+    let response = raw_request(
+        addr,
+        b"POST /solve/1/1 HTTP/1.1\r\nContent-Length: 99999999999\r\n\r\n",
+    );
+    assert!(response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"));
+
+    let response = raw_request(
+        addr,
+        b"POST /solve/1/1 HTTP/1.1\r\nContent-Length: lots\r\n\r\n",
+    );
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+
+    // the client stops sending, but keeps the connection open
+    let response = raw_request(
+        addr,
+        b"POST /solve/1/1 HTTP/1.1\r\nContent-Length: 10\r\n\r\n3   4",
+    );
+    assert!(response.starts_with("HTTP/1.1 408 Request Timeout\r\n"));
+    let response = raw_request(addr, b"GET /health HTTP/1.1\r\n");
+    assert!(response.starts_with("HTTP/1.1 408 Request Timeout\r\n"));
+
+    let response = raw_request(addr, &[b'a'; MAX_HEADERS_LEN as usize]);
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert!(response.contains("too long"));
+
+    let response = request(addr, "GET", "/health", "");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+}
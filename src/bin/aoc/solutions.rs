@@ -0,0 +1,141 @@
+use adventofcode2024::{inspect::Report, repl::Repl};
+
+macro_rules! solutions {
+    ($($day:literal $part:literal $module:ident $path:literal => $solve:expr,)*) => {
+        $(
+            // files shared between parts are compiled into each of them
+            #[allow(dead_code, clippy::duplicate_mod)]
+            #[path = $path]
+            mod $module;
+        )*
+
+        pub(crate) const SOLUTIONS: &[Solution] = &[
+            $(
+                Solution {
+                    day: $day,
+                    part: $part,
+                    solve: {
+                        use $module::solve;
+                        $solve
+                    },
+                },
+            )*
+        ];
+    };
+}
+
+#[derive(Debug)]
+pub(crate) struct Solution {
+    pub(crate) day: u8,
+    pub(crate) part: u8,
+    pub(crate) solve: fn(&str) -> String,
+}
+
+impl Solution {
+    pub(crate) fn find(day: u8, part: u8) -> Option<&'static Self> {
+        SOLUTIONS.iter().find(|s| s.day == day && s.part == part)
+    }
+
+    pub(crate) fn of_day(day: u8) -> impl Iterator<Item = &'static Self> {
+        SOLUTIONS.iter().filter(move |s| s.day == day)
+    }
+
+    pub(crate) fn input_path(&self) -> String {
+        input_path(self.day)
+    }
+}
+
+pub(crate) fn input_path(day: u8) -> String {
+    format!("{}/src/bin/day{day}/input.txt", env!("CARGO_MANIFEST_DIR"))
+}
+
+impl std::fmt::Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "day {} part {}", self.day, self.part)
+    }
+}
+
+solutions! {
+    1 1 day1_part1 "../day1/part1.rs" => |input| solve(input).to_string(),
+    1 2 day1_part2 "../day1/part2.rs" => |input| solve(input).to_string(),
+    2 1 day2_part1 "../day2/part1.rs" => |input| solve(input).to_string(),
+    2 2 day2_part2 "../day2/part2.rs" => |input| solve(input).to_string(),
+    3 1 day3_part1 "../day3/part1.rs" => |input| solve(input).to_string(),
+    3 2 day3_part2 "../day3/part2.rs" => |input| solve(input).to_string(),
+    4 1 day4_part1 "../day4/part1.rs" => |input| solve(input.as_bytes()).to_string(),
+    4 2 day4_part2 "../day4/part2.rs" => |input| solve(input.as_bytes()).to_string(),
+    5 1 day5_part1 "../day5/part1.rs" => |input| solve(input).to_string(),
+    5 2 day5_part2 "../day5/part2.rs" => |input| solve(input).to_string(),
+    6 1 day6_part1 "../day6/part1.rs" => |input| solve(input.as_bytes()).to_string(),
+    6 2 day6_part2 "../day6/part2.rs" => |input| solve(input.as_bytes()).to_string(),
+    7 1 day7_part1 "../day7/part1.rs" => |input| solve(input).to_string(),
+    7 2 day7_part2 "../day7/part2.rs" => |input| solve(input).to_string(),
+    8 1 day8_part1 "../day8/part1.rs" => |input| solve(input).to_string(),
+    8 2 day8_part2 "../day8/part2.rs" => |input| solve(input).to_string(),
+    9 1 day9_part1 "../day9/part1.rs" => |input| solve(input).to_string(),
+    9 2 day9_part2 "../day9/part2.rs" => |input| solve(input).to_string(),
+    10 1 day10_part1 "../day10/part1.rs" => |input| solve(input).to_string(),
+    10 2 day10_part2 "../day10/part2.rs" => |input| solve(input).to_string(),
+    11 1 day11_part1 "../day11/part1.rs" => |input| solve(input).to_string(),
+    11 2 day11_part2 "../day11/part2.rs" => |input| solve(input).to_string(),
+    12 1 day12_part1 "../day12/part1.rs" => |input| solve(input).to_string(),
+    12 2 day12_part2 "../day12/part2.rs" => |input| solve(input).to_string(),
+    13 1 day13_both "../day13/both.rs" => |input| solve(input).to_string(),
+    14 1 day14_part1 "../day14/part1.rs" => |input| solve(input, day14_part1::ROWS_COUNT, day14_part1::COLS_COUNT).to_string(),
+    15 1 day15_part1 "../day15/part1.rs" => |input| solve(input).to_string(),
+    15 2 day15_part2 "../day15/part2.rs" => |input| solve(input).to_string(),
+    16 1 day16_part1 "../day16/part1.rs" => |input| solve(input).to_string(),
+    16 2 day16_part2 "../day16/part2.rs" => |input| solve(input).to_string(),
+    17 1 day17_part1 "../day17/part1.rs" => |input| solve(input),
+    18 1 day18_part1 "../day18/part1.rs" => |input| solve(input).to_string(),
+    18 2 day18_part2 "../day18/part2.rs" => |input| solve(input),
+    19 1 day19_part1 "../day19/part1.rs" => |input| solve(input).to_string(),
+    19 2 day19_part2 "../day19/part2.rs" => |input| solve(input).to_string(),
+    20 1 day20_part1 "../day20/part1.rs" => |input| solve(input).to_string(),
+    20 2 day20_part2 "../day20/part2.rs" => |input| solve(input).to_string(),
+    21 1 day21_part1 "../day21/part1.rs" => |input| solve(input).to_string(),
+    21 2 day21_part2 "../day21/part2.rs" => |input| solve(input).to_string(),
+    22 1 day22_part1 "../day22/part1.rs" => |input| solve(input).to_string(),
+    22 2 day22_part2 "../day22/part2.rs" => |input| solve(input).to_string(),
+    23 1 day23_part1 "../day23/part1.rs" => |input| solve(input).to_string(),
+    23 2 day23_part2 "../day23/part2.rs" => |input| solve(input),
+    24 1 day24_part1 "../day24/part1.rs" => |input| solve(input).to_string(),
+    25 1 day25_part1 "../day25/part1.rs" => |input| solve(input).to_string(),
+}
+
+pub(crate) type ReplFn = for<'a> fn(&'a str) -> Box<dyn Repl + 'a>;
+
+pub(crate) const REPLS: &[(u8, ReplFn)] = &[
+    (16, day16_part1::repl),
+    (17, day17_part1::repl),
+    (24, day24_part1::repl),
+    (25, day25_part1::repl),
+];
+
+pub(crate) fn find_repl(day: u8) -> Option<ReplFn> {
+    REPLS.iter().find(|(d, _)| *d == day).map(|(_, f)| *f)
+}
+
+pub(crate) type InspectFn = fn(&str) -> Report;
+
+pub(crate) const INSPECTS: &[(u8, InspectFn)] = &[
+    (5, day5_part2::inspect),
+    (8, day8_part1::inspect),
+    (14, day14_part1::inspect),
+    (17, day17_part1::inspect),
+    (20, day20_part1::inspect),
+    (24, day24_part1::inspect),
+    (25, day25_part1::inspect),
+];
+
+pub(crate) fn find_inspect(day: u8) -> Option<InspectFn> {
+    INSPECTS.iter().find(|(d, _)| *d == day).map(|(_, f)| *f)
+}
+
+#[test]
+fn test() {
+    let solution = Solution::find(1, 1).unwrap();
+    let input = include_str!("../day1/input1_test.txt");
+    assert_eq!((solution.solve)(input), "11");
+    assert_eq!(Solution::of_day(1).count(), 2);
+}
@@ -0,0 +1,56 @@
+use adventofcode2024::args::take_arg;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+};
+
+/// Parses a line of two signed numbers separated by any whitespace,
+/// returns `None` for blank lines.
+pub fn parse_line(line: &str) -> Option<(i64, i64)> {
+    let mut it = line.split_whitespace();
+    let l = it.next()?;
+    let (Some(r), None) = (it.next(), it.next()) else {
+        panic!("expected two numbers, got {line:?}");
+    };
+
+    let parse = |s: &str| {
+        s.parse::<i64>()
+            .unwrap_or_else(|_| panic!("expected a number, got {s:?} in {line:?}"))
+    };
+    Some((parse(l), parse(r)))
+}
+
+pub fn parse(input: &str) -> impl Iterator<Item = (i64, i64)> + '_ {
+    input.lines().filter_map(parse_line)
+}
+
+/// Same as `parse`, but pulls lines from `reader` one at a time.
+pub fn read(reader: impl Read) -> impl Iterator<Item = (i64, i64)> {
+    BufReader::new(reader)
+        .lines()
+        .map(|line| line.unwrap())
+        .filter_map(|line| parse_line(&line))
+}
+
+/// Opens a file, `-` stands for stdin.
+pub fn open(path: &str) -> Box<dyn Read> {
+    if path == "-" {
+        Box::new(std::io::stdin())
+    } else {
+        Box::new(File::open(path).unwrap_or_else(|err| panic!("can't open {path}: {err}")))
+    }
+}
+
+/// Removes `--top <n>` from report arguments, defaults to 10.
+pub fn take_top_n(args: &mut Vec<String>) -> usize {
+    take_arg(args, "--top").map_or(10, |n| n.parse().expect("--top requires a number"))
+}
+
+#[test]
+fn test_parse_line() {
+    assert_eq!(parse_line("3   4"), Some((3, 4)));
+    assert_eq!(parse_line("3\t4"), Some((3, 4)));
+    assert_eq!(parse_line(" -3 4 "), Some((-3, 4)));
+    assert_eq!(parse_line(""), None);
+    assert_eq!(parse_line("   "), None);
+}
@@ -1,26 +1,251 @@
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+#[path = "lists.rs"]
+mod lists;
+
+// 2 columns of 16M numbers, 256MB of memory at most
+const RUN_LEN: usize = 16 * 1024 * 1024;
+
 fn main() {
-    let input = include_str!("input.txt");
-    let output = solve(input);
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    if args.first().is_some_and(|arg| arg == "report") {
+        args.remove(0);
+        let top_n = lists::take_top_n(&mut args);
+        let mut left = ExternalSort::new(RUN_LEN);
+        let mut right = ExternalSort::new(RUN_LEN);
+        let pairs: Box<dyn Iterator<Item = (i64, i64)>> = match args.first() {
+            Some(path) => Box::new(lists::read(lists::open(path))),
+            None => Box::new(lists::parse(include_str!("input.txt"))),
+        };
+        for (l, r) in pairs {
+            left.push(l);
+            right.push(r);
+        }
+        report(
+            left.finish(),
+            right.finish(),
+            top_n,
+            std::io::stdout().lock(),
+            std::io::stderr().lock(),
+        )
+        .unwrap();
+        return;
+    }
+
+    let output = match args.first() {
+        Some(path) => solve_streaming(lists::open(path), RUN_LEN),
+        None => solve(include_str!("input.txt")),
+    };
     println!("{}", output);
 }
 
-fn solve(input: &str) -> usize {
-    let mut left = vec![];
-    let mut right = vec![];
-    for line in input.lines() {
-        let (l, r) = line.split_once("   ").unwrap();
-        let l = l.parse::<usize>().unwrap();
-        let r = r.parse::<usize>().unwrap();
-        left.push(l);
-        right.push(r);
+/// Sorts a stream of numbers by spilling sorted runs of `run_len` numbers
+/// to temporary files and merging them back.
+struct ExternalSort {
+    run_len: usize,
+    buf: Vec<i64>,
+    dir: SpillDir,
+    runs: Vec<PathBuf>,
+}
+
+/// Directory with spilled runs, removed once neither the sort nor its merge needs it.
+struct SpillDir(PathBuf);
+
+impl Drop for SpillDir {
+    fn drop(&mut self) {
+        match std::fs::remove_dir_all(&self.0) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                eprintln!("can't remove {}: {err}", self.0.display());
+            }
+            _ => {}
+        }
+    }
+}
+
+impl ExternalSort {
+    fn new(run_len: usize) -> Self {
+        static ID: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "aoc-day1-{}-{}",
+            std::process::id(),
+            ID.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        Self {
+            run_len,
+            buf: vec![],
+            dir: SpillDir(dir),
+            runs: vec![],
+        }
+    }
+
+    fn push(&mut self, n: i64) {
+        self.buf.push(n);
+        if self.buf.len() == self.run_len {
+            self.spill();
+        }
+    }
+
+    fn spill(&mut self) {
+        self.buf.sort_unstable();
+
+        std::fs::create_dir_all(&self.dir.0).unwrap();
+        let path = self.dir.0.join(format!("run{}", self.runs.len()));
+        let mut writer = BufWriter::new(File::create(&path).unwrap());
+        for n in self.buf.drain(..) {
+            writer.write_all(&n.to_le_bytes()).unwrap();
+        }
+        writer.flush().unwrap();
+
+        self.runs.push(path);
+    }
+
+    fn finish(mut self) -> Box<dyn Iterator<Item = i64>> {
+        if self.runs.is_empty() {
+            self.buf.sort_unstable();
+            return Box::new(std::mem::take(&mut self.buf).into_iter());
+        }
+        if !self.buf.is_empty() {
+            self.spill();
+        }
+
+        let mut readers = self
+            .runs
+            .iter()
+            .map(|path| BufReader::new(File::open(path).unwrap()))
+            .collect::<Vec<_>>();
+        let mut heap = BinaryHeap::new();
+        for (idx, reader) in readers.iter_mut().enumerate() {
+            if let Some(n) = read_i64(reader) {
+                heap.push(Reverse((n, idx)));
+            }
+        }
+
+        Box::new(Merge {
+            readers,
+            heap,
+            _dir: self.dir,
+        })
+    }
+}
+
+fn read_i64(reader: &mut impl Read) -> Option<i64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes).ok()?;
+    Some(i64::from_le_bytes(bytes))
+}
+
+struct Merge {
+    readers: Vec<BufReader<File>>,
+    heap: BinaryHeap<Reverse<(i64, usize)>>,
+    _dir: SpillDir,
+}
+
+impl Iterator for Merge {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<i64> {
+        let Reverse((n, idx)) = self.heap.pop()?;
+        if let Some(next) = read_i64(&mut self.readers[idx]) {
+            self.heap.push(Reverse((next, idx)));
+        }
+        Some(n)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Pairing {
+    distance: u64,
+    left: i64,
+    right: i64,
+}
+
+fn pairings(
+    left: impl Iterator<Item = i64>,
+    right: impl Iterator<Item = i64>,
+) -> impl Iterator<Item = Pairing> {
+    left.zip(right).map(|(left, right)| Pairing {
+        distance: left.abs_diff(right),
+        left,
+        right,
+    })
+}
+
+fn add_distance(total: u128, pairing: &Pairing) -> u128 {
+    total
+        .checked_add(u128::from(pairing.distance))
+        .expect("total distance overflows u128")
+}
+
+fn total_distance(left: impl Iterator<Item = i64>, right: impl Iterator<Item = i64>) -> u128 {
+    pairings(left, right).fold(0, |total, pairing| add_distance(total, &pairing))
+}
+
+/// Writes every pairing of sorted lists to `csv` and the `top_n` most distant pairs to `summary`.
+fn report(
+    left: impl Iterator<Item = i64>,
+    right: impl Iterator<Item = i64>,
+    top_n: usize,
+    mut csv: impl Write,
+    mut summary: impl Write,
+) -> std::io::Result<()> {
+    let mut top = BinaryHeap::new();
+    let mut total = 0_u128;
+
+    writeln!(csv, "left,right,distance")?;
+    for pairing in pairings(left, right) {
+        writeln!(
+            csv,
+            "{},{},{}",
+            pairing.left, pairing.right, pairing.distance
+        )?;
+        total = add_distance(total, &pairing);
+
+        top.push(Reverse(pairing));
+        if top.len() > top_n {
+            top.pop();
+        }
+    }
+
+    writeln!(summary, "total distance: {total}")?;
+    writeln!(summary, "top {top_n} pairs by distance:")?;
+    for Reverse(pairing) in top.into_sorted_vec() {
+        let share = pairing.distance as f64 * 100.0 / total.max(1) as f64;
+        writeln!(
+            summary,
+            "  {} - {} = {} ({share:.1}%)",
+            pairing.left, pairing.right, pairing.distance
+        )?;
     }
+
+    Ok(())
+}
+
+pub fn solve(input: &str) -> u128 {
+    let (mut left, mut right): (Vec<_>, Vec<_>) = lists::parse(input).unzip();
     left.sort_unstable();
     right.sort_unstable();
 
-    left.into_iter()
-        .zip(right)
-        .map(|(l, r)| l.abs_diff(r))
-        .sum()
+    total_distance(left.into_iter(), right.into_iter())
+}
+
+pub fn solve_streaming(reader: impl Read, run_len: usize) -> u128 {
+    let mut left = ExternalSort::new(run_len);
+    let mut right = ExternalSort::new(run_len);
+    for (l, r) in lists::read(reader) {
+        left.push(l);
+        right.push(r);
+    }
+
+    total_distance(left.finish(), right.finish())
 }
 
 #[test]
@@ -28,4 +253,51 @@ fn test() {
     let input = include_str!("input1_test.txt");
     let output = solve(input);
     assert_eq!(output, 11);
+
+    assert_eq!(solve_streaming(input.as_bytes(), 2), 11);
+    assert_eq!(solve_streaming(input.as_bytes(), RUN_LEN), 11);
+
+    let input = "3\t-4\n-4 3\n\n2  5\n";
+    assert_eq!(solve(input), 3);
+    assert_eq!(solve_streaming(input.as_bytes(), 1), 3);
+
+    // every distance fits u64, but their sum doesn't
+    let input = "-9223372036854775808 9223372036854775807\n".repeat(2);
+    assert_eq!(solve(&input), 36893488147419103230);
+    assert_eq!(solve_streaming(input.as_bytes(), 1), 36893488147419103230);
+
+    let mut sort = ExternalSort::new(1);
+    sort.push(1);
+    let dir = sort.dir.0.clone();
+    assert!(dir.exists());
+    drop(sort);
+    assert!(!dir.exists());
+}
+
+#[test]
+fn test_report() {
+    let input = include_str!("input1_test.txt");
+    let (mut left, mut right): (Vec<_>, Vec<_>) = lists::parse(input).unzip();
+    left.sort_unstable();
+    right.sort_unstable();
+
+    let mut csv = vec![];
+    let mut summary = vec![];
+    report(
+        left.into_iter(),
+        right.into_iter(),
+        2,
+        &mut csv,
+        &mut summary,
+    )
+    .unwrap();
+
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "left,right,distance\n1,3,2\n2,3,1\n3,3,0\n3,4,1\n3,5,2\n4,9,5\n"
+    );
+    assert_eq!(
+        String::from_utf8(summary).unwrap(),
+        "total distance: 11\ntop 2 pairs by distance:\n  4 - 9 = 5 (45.5%)\n  3 - 5 = 2 (18.2%)\n"
+    );
 }
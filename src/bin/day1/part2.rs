@@ -1,26 +1,132 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+
+#[path = "lists.rs"]
+mod lists;
 
 fn main() {
-    let input = include_str!("input.txt");
-    let output = solve(input);
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    if args.first().is_some_and(|arg| arg == "report") {
+        args.remove(0);
+        let top_n = lists::take_top_n(&mut args);
+        let contributions = match args.first() {
+            Some(path) => contributions(lists::read(lists::open(path))),
+            None => contributions(lists::parse(include_str!("input.txt"))),
+        };
+        report(
+            &contributions,
+            top_n,
+            std::io::stdout().lock(),
+            std::io::stderr().lock(),
+        )
+        .unwrap();
+        return;
+    }
+
+    let output = match args.first() {
+        Some(path) => solve_streaming(lists::open(path)),
+        None => solve(include_str!("input.txt")),
+    };
     println!("{}", output);
 }
 
-fn solve(input: &str) -> usize {
-    let mut left = vec![];
-    let mut right = HashMap::<usize, usize>::new();
-    for line in input.lines() {
-        let (l, r) = line.split_once("   ").unwrap();
-        let l = l.parse::<usize>().unwrap();
-        let r = r.parse::<usize>().unwrap();
-        left.push(l);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Contribution {
+    value: i64,
+    left_count: i64,
+    right_count: i64,
+}
+
+impl Contribution {
+    fn product(self) -> i128 {
+        i128::from(self.value)
+            .checked_mul(i128::from(self.left_count))
+            .and_then(|n| n.checked_mul(i128::from(self.right_count)))
+            .expect("similarity contribution overflows i128")
+    }
+}
 
+fn total(contributions: impl Iterator<Item = Contribution>) -> i128 {
+    contributions
+        .map(Contribution::product)
+        .try_fold(0_i128, i128::checked_add)
+        .expect("similarity score overflows i128")
+}
+
+/// Similarity score contribution of every distinct number from the left list, sorted by number.
+fn contributions(pairs: impl Iterator<Item = (i64, i64)>) -> Vec<Contribution> {
+    let mut left = HashMap::<i64, i64>::new();
+    let mut right = HashMap::<i64, i64>::new();
+    for (l, r) in pairs {
+        *left.entry(l).or_default() += 1;
         *right.entry(r).or_default() += 1;
     }
 
-    left.into_iter()
-        .map(|l| l * right.get(&l).copied().unwrap_or_default())
-        .sum()
+    let mut out = left
+        .into_iter()
+        .map(|(value, left_count)| Contribution {
+            value,
+            left_count,
+            right_count: right.get(&value).copied().unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+    out.sort_unstable_by_key(|c| c.value);
+    out
+}
+
+fn similarity(pairs: impl Iterator<Item = (i64, i64)>) -> i128 {
+    total(contributions(pairs).into_iter())
+}
+
+/// Writes all contributions to `csv` and the `top_n` biggest ones to `summary`.
+fn report(
+    contributions: &[Contribution],
+    top_n: usize,
+    mut csv: impl Write,
+    mut summary: impl Write,
+) -> std::io::Result<()> {
+    writeln!(csv, "value,left_count,right_count,product")?;
+    for c in contributions {
+        writeln!(
+            csv,
+            "{},{},{},{}",
+            c.value,
+            c.left_count,
+            c.right_count,
+            c.product()
+        )?;
+    }
+
+    let total = total(contributions.iter().copied());
+    let mut top = contributions.to_vec();
+    top.sort_by_key(|c| std::cmp::Reverse(c.product().abs()));
+    top.truncate(top_n);
+
+    writeln!(summary, "similarity score: {total}")?;
+    writeln!(summary, "top {top_n} contributions:")?;
+    for c in top {
+        writeln!(
+            summary,
+            "  {} x {} x {} = {}",
+            c.value,
+            c.left_count,
+            c.right_count,
+            c.product()
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn solve(input: &str) -> i128 {
+    similarity(lists::parse(input))
+}
+
+pub fn solve_streaming(reader: impl Read) -> i128 {
+    similarity(lists::read(reader))
 }
 
 #[test]
@@ -28,4 +134,32 @@ fn test() {
     let input = include_str!("input2_test.txt");
     let output = solve(input);
     assert_eq!(output, 31);
+
+    assert_eq!(solve_streaming(input.as_bytes()), 31);
+
+    let input = "-3\t-3\n-3 4\n\n2  -3\n";
+    assert_eq!(solve(input), -3 * 2 * 2);
+
+    let max = i64::MAX;
+    let input = format!("{max} {max}\n{max} {max}\n");
+    assert_eq!(solve(&input), i128::from(max) * 4);
+}
+
+#[test]
+fn test_report() {
+    let input = include_str!("input2_test.txt");
+    let contributions = contributions(lists::parse(input));
+
+    let mut csv = vec![];
+    let mut summary = vec![];
+    report(&contributions, 2, &mut csv, &mut summary).unwrap();
+
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "value,left_count,right_count,product\n1,1,0,0\n2,1,0,0\n3,3,3,27\n4,1,1,4\n"
+    );
+    assert_eq!(
+        String::from_utf8(summary).unwrap(),
+        "similarity score: 31\ntop 2 contributions:\n  3 x 3 x 3 = 27\n  4 x 1 x 1 = 4\n"
+    );
 }
@@ -108,7 +108,7 @@ impl Matrix {
     }
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let matrix = Matrix::parse(input);
     let mut set = HashSet::new();
     for start in matrix.zeroes() {
@@ -106,7 +106,7 @@ impl Matrix {
     }
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let matrix = Matrix::parse(input);
     let mut out = 0;
     for start in matrix.zeroes() {
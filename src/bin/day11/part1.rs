@@ -21,7 +21,7 @@ impl Stone {
     }
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let mut stones = vec![];
     for line in input.trim().split(' ') {
         stones.push(Stone(line.parse().unwrap()));
@@ -17,7 +17,7 @@ impl<'a> Writer<'a> {
         Writer { buf, offset: 0 }
     }
 
-    pub fn as_str(self) -> Option<&'a str> {
+    pub fn into_str(self) -> Option<&'a str> {
         if self.offset <= self.buf.len() {
             // only successful concats of str - must be a valid str.
             Some(core::str::from_utf8(&self.buf[..self.offset]).unwrap())
@@ -55,7 +55,7 @@ pub fn write_to<'a>(
 ) -> Result<&'a str, core::fmt::Error> {
     let mut w = Writer::new(buf);
     core::fmt::write(&mut w, args)?;
-    w.as_str().ok_or(core::fmt::Error)
+    w.into_str().ok_or(core::fmt::Error)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -70,8 +70,8 @@ impl Stone {
         let mut buf = [0; 20];
         let mut writer = Writer::new(&mut buf);
         write!(&mut writer, "{}", self.0).unwrap();
-        let s = writer.as_str().unwrap();
-        if s.len() % 2 == 0 {
+        let s = writer.into_str().unwrap();
+        if s.len().is_multiple_of(2) {
             let (l, r) = s.split_at(s.len() / 2);
             return (Stone(l.parse().unwrap()), Some(Stone(r.parse().unwrap())));
         }
@@ -114,7 +114,7 @@ impl Line {
     }
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let mut line = Line::parse(input);
 
     for _ in 0..75 {
@@ -132,7 +132,7 @@ impl Shape {
     }
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let matrix = Matrix::parse(input);
     let shapes = matrix.shapes();
 
@@ -272,7 +272,7 @@ fn optimize(sides: &mut HashSet<Side>) {
     }
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let matrix = Matrix::parse(input);
     let shapes = matrix.shapes();
 
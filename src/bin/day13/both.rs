@@ -107,7 +107,7 @@ impl Machine {
     }
 }
 
-fn solve(input: &str) -> u64 {
+pub fn solve(input: &str) -> u64 {
     let mut machines = vec![];
     for subinput in input.trim().split("\n\n") {
         machines.push(Machine::parse(subinput));
@@ -1,11 +1,16 @@
+use adventofcode2024::inspect::Report;
 use std::ops::RangeInclusive;
 
 fn main() {
     let input = include_str!("input.txt");
-    let output = solve(input, 103, 101);
+    let output = solve(input, ROWS_COUNT, COLS_COUNT);
     println!("{}", output);
 }
 
+/// Size of the room in the real puzzle, examples use a smaller one.
+pub const ROWS_COUNT: u64 = 103;
+pub const COLS_COUNT: u64 = 101;
+
 #[derive(Debug, Clone, Copy)]
 struct Location {
     row: i64,
@@ -71,6 +76,14 @@ impl Robot {
         }
     }
 
+    fn parse_all(input: &str, rows_count: u64, cols_count: u64) -> Vec<Self> {
+        input
+            .trim()
+            .lines()
+            .map(|l| Self::parse(l, rows_count, cols_count))
+            .collect()
+    }
+
     fn location_after_seconds(&self, n: u64) -> Location {
         self.starting_loc
             .add(self.speed.0 * n as i64, self.speed.1 * n as i64)
@@ -85,7 +98,7 @@ struct Quadrant {
 
 impl Quadrant {
     fn split(n: u64) -> (u64, u64) {
-        if n % 2 == 0 {
+        if n.is_multiple_of(2) {
             (n / 2 - 1, n / 2)
         } else {
             (n / 2 - 1, n / 2 + 1)
@@ -143,12 +156,37 @@ fn test_split() {
     assert_eq!(Quadrant::split(6), (2, 3));
 }
 
-fn solve(input: &str, rows_count: u64, cols_count: u64) -> u64 {
-    let robots = input
-        .trim()
-        .lines()
-        .map(|l| Robot::parse(l, rows_count, cols_count))
-        .collect::<Vec<_>>();
+pub fn inspect(input: &str) -> Report {
+    let mut report = Report::new();
+    let (rows_count, cols_count) = (ROWS_COUNT, COLS_COUNT);
+    let robots = Robot::parse_all(input, rows_count, cols_count);
+
+    report.fact("robots", robots.len());
+    report.fact("room", format!("{rows_count}x{cols_count}"));
+
+    report.check(
+        "robots start inside the room",
+        robots
+            .iter()
+            .find(|robot| {
+                let Location { row, col } = robot.starting_loc;
+                !(0..rows_count as i64).contains(&row) || !(0..cols_count as i64).contains(&col)
+            })
+            .map(|robot| format!("robot at {:?}", robot.starting_loc)),
+    );
+    report.check(
+        "no robot stands still",
+        robots
+            .iter()
+            .find(|robot| robot.speed == (0, 0))
+            .map(|robot| format!("robot at {:?}", robot.starting_loc)),
+    );
+
+    report
+}
+
+pub fn solve(input: &str, rows_count: u64, cols_count: u64) -> u64 {
+    let robots = Robot::parse_all(input, rows_count, cols_count);
 
     let quadrants = Quadrant::all(rows_count, cols_count);
     let mut count = [0; 4];
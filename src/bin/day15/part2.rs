@@ -362,7 +362,7 @@ impl HorizontalDirection {
     }
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let (matrix, insns) = input.split_once("\n\n").unwrap();
     let mut matrix = Matrix::parse(matrix);
 
@@ -1,3 +1,4 @@
+use adventofcode2024::repl::Repl;
 use std::collections::{HashMap, VecDeque};
 
 fn main() {
@@ -169,20 +170,49 @@ impl Matrix {
     }
 
     fn get_best_path(&self) -> usize {
+        self.best_path().0
+    }
+
+    fn best_path(&self) -> (usize, Vec<Location>) {
         let mut queue = VecDeque::new();
         queue.push_back((self.start_loc, Direction::Right, 0));
 
         let mut best_score = BestScore::new(self.start_loc);
+        let mut came_from = HashMap::new();
 
         while let Some((loc, dir, score)) = queue.pop_front() {
             for (next_loc, next_dir, cost) in self.siblings(loc, dir) {
                 if best_score.inc(next_loc, score + cost) {
+                    came_from.insert(next_loc, loc);
                     queue.push_back((next_loc, next_dir, score + cost));
                 }
             }
         }
 
-        best_score.get(self.end_loc).unwrap()
+        let mut path = vec![self.end_loc];
+        while let Some(prev) = came_from.get(path.last().unwrap()) {
+            path.push(*prev);
+        }
+        path.reverse();
+
+        (best_score.get(self.end_loc).unwrap(), path)
+    }
+
+    fn render_path(&self, path: &[Location]) -> String {
+        let mut out = String::new();
+        for row in 0..self.rows_count {
+            for col in 0..self.cols_count {
+                let loc = Location { row, col };
+                let cell = self.get(loc);
+                if cell == Cell::Free && path.contains(&loc) {
+                    out.push('O');
+                } else {
+                    out.push_str(&cell.to_string());
+                }
+            }
+            out.push('\n');
+        }
+        out
     }
 
     fn try_move(&self, loc: Location, dir: Direction) -> Option<Location> {
@@ -256,7 +286,43 @@ impl BestScore {
     }
 }
 
-fn solve(input: &str) -> usize {
+struct MatrixRepl {
+    matrix: Matrix,
+}
+
+impl Repl for MatrixRepl {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("render", "print the maze"),
+            ("score", "print the score of the best path"),
+            ("path", "print the maze with the best path marked as O"),
+        ]
+    }
+
+    fn exec(&mut self, command: &str, _args: &[&str]) -> Result<String, String> {
+        match command {
+            "render" => Ok(self.matrix.to_string()),
+            "score" => Ok(self.matrix.get_best_path().to_string()),
+            "path" => {
+                let (score, path) = self.matrix.best_path();
+                Ok(format!(
+                    "{}score: {score}, length: {}",
+                    self.matrix.render_path(&path),
+                    path.len() - 1
+                ))
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+pub fn repl(input: &str) -> Box<dyn Repl + '_> {
+    Box::new(MatrixRepl {
+        matrix: Matrix::parse(input),
+    })
+}
+
+pub fn solve(input: &str) -> usize {
     let matrix = Matrix::parse(input);
     matrix.get_best_path()
 }
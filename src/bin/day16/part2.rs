@@ -295,7 +295,7 @@ impl BestScore {
     }
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let matrix = Matrix::parse(input);
     let path = matrix.get_best_paths();
     path.len()
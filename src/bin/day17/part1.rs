@@ -1,10 +1,12 @@
+use adventofcode2024::{inspect::Report, repl::Repl};
+
 fn main() {
     let input = include_str!("input.txt");
     let output = solve(input);
     println!("{}", output);
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 struct Registers {
     a: usize,
     b: usize,
@@ -28,7 +30,7 @@ impl Registers {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Program {
     instruction_pointer: usize,
     tape: Vec<usize>,
@@ -99,6 +101,21 @@ impl Instruction {
     }
 }
 
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let opcode = format!("{:?}", self.opcode).to_lowercase();
+        match (&self.opcode, self.operand) {
+            (InstructionOpCode::Bxl | InstructionOpCode::Jnz | InstructionOpCode::Bxc, operand) => {
+                write!(f, "{opcode} {}", operand.literal())
+            }
+            (_, Operand::Value(v)) => write!(f, "{opcode} {v}"),
+            (_, Operand::A) => write!(f, "{opcode} A"),
+            (_, Operand::B) => write!(f, "{opcode} B"),
+            (_, Operand::C) => write!(f, "{opcode} C"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 enum Operand {
     Value(usize),
@@ -137,22 +154,39 @@ impl Operand {
     }
 }
 
-fn solve(input: &str) -> String {
-    let (registers, program) = input.split_once("\n\n").unwrap();
+#[derive(Debug, Clone)]
+struct Machine {
+    registers: Registers,
+    program: Program,
+    out: Vec<usize>,
+}
 
-    let mut registers = Registers::parse(registers);
-    let mut program = Program::parse(program);
+impl Machine {
+    fn parse(input: &str) -> Self {
+        let (registers, program) = input.split_once("\n\n").unwrap();
 
-    let mut out = vec![];
+        Self {
+            registers: Registers::parse(registers),
+            program: Program::parse(program),
+            out: vec![],
+        }
+    }
+
+    fn step(&mut self) -> bool {
+        let Some(Instruction {
+            opcode,
+            operand: combo,
+        }) = self.program.next_insn()
+        else {
+            return false;
+        };
+
+        let registers = &mut self.registers;
+        let program = &mut self.program;
 
-    while let Some(Instruction {
-        opcode,
-        operand: combo,
-    }) = program.next_insn()
-    {
         match opcode {
             InstructionOpCode::Adv => {
-                let arg = combo.resolve(&registers);
+                let arg = combo.resolve(registers);
                 registers.a /= 2_usize.pow(arg as u32);
                 program.instruction_pointer += 2;
             }
@@ -162,7 +196,7 @@ fn solve(input: &str) -> String {
                 program.instruction_pointer += 2;
             }
             InstructionOpCode::Bst => {
-                let arg = combo.resolve(&registers);
+                let arg = combo.resolve(registers);
                 registers.b = arg % 8;
                 program.instruction_pointer += 2;
             }
@@ -179,24 +213,177 @@ fn solve(input: &str) -> String {
                 program.instruction_pointer += 2;
             }
             InstructionOpCode::Out => {
-                let arg = combo.resolve(&registers);
-                out.push(format!("{}", arg % 8));
+                let arg = combo.resolve(registers);
+                self.out.push(arg % 8);
                 program.instruction_pointer += 2;
             }
             InstructionOpCode::Bdv => {
-                let arg = combo.resolve(&registers);
+                let arg = combo.resolve(registers);
                 registers.b = registers.a / 2_usize.pow(arg as u32);
                 program.instruction_pointer += 2;
             }
             InstructionOpCode::Cdv => {
-                let arg = combo.resolve(&registers);
+                let arg = combo.resolve(registers);
                 registers.c = registers.a / 2_usize.pow(arg as u32);
                 program.instruction_pointer += 2;
             }
         }
+
+        true
+    }
+
+    fn run(&mut self) {
+        while self.step() {}
+    }
+
+    fn output(&self) -> String {
+        self.out
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
     }
+}
+
+impl std::fmt::Display for Machine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Registers { a, b, c } = self.registers;
+        writeln!(f, "A: {a}, B: {b}, C: {c}")?;
 
-    out.join(",")
+        for (ip, pair) in self.program.tape.chunks(2).enumerate() {
+            let marker = if ip * 2 == self.program.instruction_pointer {
+                "->"
+            } else {
+                "  "
+            };
+            match pair {
+                [l, r] => writeln!(f, "{marker} {:>3}: {}", ip * 2, Instruction::parse(*l, *r))?,
+                _ => writeln!(f, "{marker} {:>3}: {pair:?}", ip * 2)?,
+            }
+        }
+
+        writeln!(f, "Output: {}", self.output())
+    }
+}
+
+struct MachineRepl {
+    initial: Machine,
+    current: Machine,
+}
+
+impl Repl for MachineRepl {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("render", "print registers, program and output"),
+            ("step [n]", "execute n instructions (default 1)"),
+            ("run", "execute until the program halts"),
+            (
+                "eval a=<n> [b=<n>] [c=<n>]",
+                "run from scratch with given registers",
+            ),
+            ("reset", "restore the parsed state"),
+        ]
+    }
+
+    fn exec(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "render" => Ok(self.current.to_string()),
+            "step" => {
+                let n = match args {
+                    [] => 1,
+                    [n] => n.parse().map_err(|_| format!("invalid step count {n:?}"))?,
+                    _ => return Err(String::from("expected at most one argument")),
+                };
+                for _ in 0..n {
+                    if !self.current.step() {
+                        break;
+                    }
+                }
+                Ok(self.current.to_string())
+            }
+            "run" => {
+                self.current.run();
+                Ok(self.current.output())
+            }
+            "eval" => {
+                let mut machine = self.initial.clone();
+                for arg in args {
+                    let (register, value) = arg
+                        .split_once('=')
+                        .ok_or_else(|| format!("expected <register>=<value>, got {arg:?}"))?;
+                    let value = value
+                        .parse()
+                        .map_err(|_| format!("invalid register value {value:?}"))?;
+                    match register {
+                        "a" | "A" => machine.registers.a = value,
+                        "b" | "B" => machine.registers.b = value,
+                        "c" | "C" => machine.registers.c = value,
+                        _ => return Err(format!("unknown register {register:?}")),
+                    }
+                }
+                machine.run();
+                Ok(machine.output())
+            }
+            "reset" => {
+                self.current = self.initial.clone();
+                Ok(self.current.to_string())
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+pub fn inspect(input: &str) -> Report {
+    let mut report = Report::new();
+    let (registers, program) = input.split_once("\n\n").unwrap();
+    let Registers { a, b, c } = Registers::parse(registers);
+    let tape = Program::parse(program).tape;
+
+    report.fact("registers", format!("A: {a}, B: {b}, C: {c}"));
+    report.fact("program length", tape.len());
+
+    report.check(
+        "program consists of 3-bit numbers",
+        tape.iter()
+            .position(|n| *n > 7)
+            .map(|idx| format!("{} at {idx}", tape[idx])),
+    );
+    report.check(
+        "program consists of opcode/operand pairs",
+        (!tape.len().is_multiple_of(2)).then(|| format!("odd length {}", tape.len())),
+    );
+    report.check(
+        "reserved combo operand 7 is never used",
+        tape.chunks(2)
+            .enumerate()
+            .find_map(|(idx, pair)| match pair {
+                [opcode, 7] if ![1, 3, 4].contains(opcode) => {
+                    Some(format!("instruction at {}", idx * 2))
+                }
+                _ => None,
+            }),
+    );
+    report.check(
+        "program is a single loop ending with jnz 0",
+        (!tape.ends_with(&[3, 0]))
+            .then(|| format!("ends with {:?}", &tape[tape.len().saturating_sub(2)..])),
+    );
+
+    report
+}
+
+pub fn repl(input: &str) -> Box<dyn Repl + '_> {
+    let machine = Machine::parse(input);
+    Box::new(MachineRepl {
+        initial: machine.clone(),
+        current: machine,
+    })
+}
+
+pub fn solve(input: &str) -> String {
+    let mut machine = Machine::parse(input);
+    machine.run();
+    machine.output()
 }
 
 #[test]
@@ -205,3 +392,17 @@ fn test1() {
     let output = solve(input);
     assert_eq!(output, "4,6,3,5,6,3,5,2,1,0".to_string());
 }
+
+#[test]
+fn test_repl() {
+    let input = include_str!("input_test1.txt");
+    let mut repl = repl(input);
+    assert_eq!(
+        repl.exec("eval", &["a=729"]),
+        Ok(String::from("4,6,3,5,6,3,5,2,1,0"))
+    );
+    assert_eq!(
+        repl.exec("eval", &["a=2024"]),
+        Ok(String::from("4,2,5,6,7,7,7,7,3,1,0"))
+    );
+}
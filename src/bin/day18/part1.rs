@@ -109,7 +109,7 @@ impl std::fmt::Display for Matrix {
     }
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let mut matrix = Matrix::parse(input);
 
     matrix.shortest_path()
@@ -103,7 +103,7 @@ impl std::fmt::Display for Matrix {
     }
 }
 
-fn solve(input: &str) -> String {
+pub fn solve(input: &str) -> String {
     let bytes = input
         .trim()
         .lines()
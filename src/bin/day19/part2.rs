@@ -4,7 +4,7 @@ fn main() {
     println!("{}", output);
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let (patterns, lines) = input.trim().split_once("\n\n").unwrap();
     let patterns = patterns.split(", ").collect::<Vec<_>>();
 
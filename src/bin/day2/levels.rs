@@ -0,0 +1,316 @@
+use adventofcode2024::args::take_flag;
+use std::{io::Write, ops::RangeInclusive};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dir {
+    Dec,
+    Inc,
+    Invalid,
+}
+
+impl Dir {
+    pub fn from_cons(l: usize, r: usize) -> Self {
+        match l.cmp(&r) {
+            std::cmp::Ordering::Less => Self::Inc,
+            std::cmp::Ordering::Equal => Self::Invalid,
+            std::cmp::Ordering::Greater => Self::Dec,
+        }
+    }
+}
+
+pub fn parse(line: &str) -> Vec<usize> {
+    line.split_whitespace()
+        .map(|e| e.parse::<usize>().unwrap())
+        .collect()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// Two adjacent levels are equal.
+    NoDirection,
+    /// Levels go against the direction of the first two levels.
+    DirectionChange { expected: Dir, got: Dir },
+    /// Adjacent levels differ by a number out of allowed steps.
+    Step { diff: usize },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Index of the first level that breaks the rules.
+    pub index: usize,
+    pub violation: Violation,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rules {
+    /// How many levels the Problem Dampener is allowed to remove.
+    pub max_removals: usize,
+    /// Allowed difference between adjacent levels.
+    pub steps: RangeInclusive<usize>,
+}
+
+impl Rules {
+    pub fn new(max_removals: usize) -> Self {
+        Self {
+            max_removals,
+            steps: 1..=3,
+        }
+    }
+
+    /// Parses `--removals <k>` and `--steps <min>..=<max>` on top of the defaults.
+    pub fn with_args(mut self, mut args: impl Iterator<Item = String>) -> Self {
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .unwrap_or_else(|| panic!("{arg} requires a value"));
+            match arg.as_str() {
+                "--removals" => self.max_removals = value.parse().unwrap(),
+                "--steps" => {
+                    let (min, max) = value
+                        .split_once("..=")
+                        .unwrap_or_else(|| panic!("expected <min>..=<max>, got {value}"));
+                    self.steps = min.parse().unwrap()..=max.parse().unwrap();
+                }
+                _ => panic!("unknown argument {arg}"),
+            }
+        }
+        self
+    }
+
+    fn is_step_valid(&self, dir: Dir, prev: usize, next: usize) -> bool {
+        Dir::from_cons(prev, next) == dir && self.steps.contains(&prev.abs_diff(next))
+    }
+
+    /// Checks if a report becomes safe after removing at most `max_removals` levels, O(n·k).
+    pub fn is_safe(&self, levels: &[usize]) -> bool {
+        self.removals(levels).is_some()
+    }
+
+    /// Indices of levels to remove to make a report safe,
+    /// `None` if it needs more than `max_removals`.
+    pub fn removals(&self, levels: &[usize]) -> Option<Vec<usize>> {
+        if self.diagnose(levels).is_none() {
+            return Some(vec![]);
+        }
+
+        [Dir::Inc, Dir::Dec]
+            .into_iter()
+            .map(|dir| self.min_removals(levels, dir))
+            .filter(|removed| removed.len() <= self.max_removals)
+            .min_by_key(|removed| removed.len())
+    }
+
+    /// Minimal set of levels to remove so that the rest goes in `dir`,
+    /// sets bigger than `max_removals` are not necessarily minimal.
+    fn min_removals(&self, levels: &[usize], dir: Dir) -> Vec<usize> {
+        let n = levels.len();
+        // best[i] = removals needed for levels[..=i] if levels[i] is kept,
+        // came_from[i] = previous kept level
+        let mut best = vec![0; n];
+        let mut came_from = vec![None; n];
+        let mut last_kept = 0;
+
+        for i in 0..n {
+            best[i] = i;
+            // keeping a level further back requires removing more than max_removals in between
+            for prev in i.saturating_sub(self.max_removals + 1)..i {
+                let removals = best[prev] + (i - prev - 1);
+                if self.is_step_valid(dir, levels[prev], levels[i]) && removals < best[i] {
+                    best[i] = removals;
+                    came_from[i] = Some(prev);
+                }
+            }
+            if best[i] + (n - 1 - i) < best[last_kept] + (n - 1 - last_kept) {
+                last_kept = i;
+            }
+        }
+
+        let mut kept = vec![false; n];
+        let mut cur = Some(last_kept);
+        while let Some(i) = cur {
+            kept[i] = true;
+            cur = came_from[i];
+        }
+        (0..n).filter(|i| !kept[*i]).collect()
+    }
+
+    /// First rule violation of a report as is, without removing any levels.
+    pub fn diagnose(&self, levels: &[usize]) -> Option<Diagnostic> {
+        let starting_dir = Dir::from_cons(*levels.first()?, *levels.get(1)?);
+
+        for (index, pair) in levels.windows(2).enumerate() {
+            let (prev, next) = (pair[0], pair[1]);
+            let dir = Dir::from_cons(prev, next);
+
+            let violation = if dir == Dir::Invalid {
+                Some(Violation::NoDirection)
+            } else if dir != starting_dir {
+                Some(Violation::DirectionChange {
+                    expected: starting_dir,
+                    got: dir,
+                })
+            } else if !self.steps.contains(&prev.abs_diff(next)) {
+                Some(Violation::Step {
+                    diff: prev.abs_diff(next),
+                })
+            } else {
+                None
+            };
+
+            if let Some(violation) = violation {
+                return Some(Diagnostic {
+                    index: index + 1,
+                    violation,
+                });
+            }
+        }
+
+        None
+    }
+
+    /// Writes every report followed by why it's unsafe and how to fix it.
+    pub fn explain(&self, input: &str, mut out: impl Write) -> std::io::Result<()> {
+        for line in input.lines() {
+            let levels = parse(line);
+            write!(out, "{line}: ")?;
+
+            let Some(Diagnostic { index, violation }) = self.diagnose(&levels) else {
+                writeln!(out, "safe")?;
+                continue;
+            };
+
+            let (prev, next) = (levels[index - 1], levels[index]);
+            write!(out, "unsafe at index {index}, {prev} -> {next} ")?;
+            match violation {
+                Violation::NoDirection => write!(out, "neither increases nor decreases")?,
+                Violation::DirectionChange { expected, got } => {
+                    write!(out, "goes {got:?} after starting {expected:?}")?
+                }
+                Violation::Step { diff } => write!(
+                    out,
+                    "changes by {diff}, allowed {}..={}",
+                    self.steps.start(),
+                    self.steps.end()
+                )?,
+            }
+
+            if self.max_removals > 0 {
+                match self.removals(&levels) {
+                    Some(removed) => {
+                        let removed = removed
+                            .iter()
+                            .map(|i| format!("index {i} ({})", levels[*i]))
+                            .collect::<Vec<_>>();
+                        write!(out, "; safe after removing {}", removed.join(", "))?
+                    }
+                    None => write!(
+                        out,
+                        "; can't be fixed with {} removal(s)",
+                        self.max_removals
+                    )?,
+                }
+            }
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn solve_with(input: &str, rules: &Rules) -> usize {
+    input
+        .lines()
+        .filter(|line| rules.is_safe(&parse(line)))
+        .count()
+}
+
+/// Entry point of both parts, they only differ in how many levels can be removed by default.
+pub fn run(input: &str, default_max_removals: usize) {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let explain = take_flag(&mut args, "--explain");
+    let rules = Rules::new(default_max_removals).with_args(args.into_iter());
+
+    if explain {
+        rules.explain(input, std::io::stdout().lock()).unwrap();
+    } else {
+        let output = solve_with(input, &rules);
+        println!("{}", output);
+    }
+}
+
+#[test]
+fn test_is_safe() {
+    /// Fewest removals that make a report safe, if within `max_removals`.
+    fn brute_force(rules: &Rules, levels: &[usize]) -> Option<usize> {
+        let n = levels.len();
+        (0_u32..1 << n)
+            .filter(|mask| (n - mask.count_ones() as usize) <= rules.max_removals)
+            .filter(|mask| {
+                let kept = (0..n)
+                    .filter(|i| mask & (1 << i) != 0)
+                    .map(|i| levels[i])
+                    .collect::<Vec<_>>();
+                kept.len() < 2
+                    || [Dir::Inc, Dir::Dec].into_iter().any(|dir| {
+                        kept.windows(2)
+                            .all(|w| rules.is_step_valid(dir, w[0], w[1]))
+                    })
+            })
+            .map(|mask| n - mask.count_ones() as usize)
+            .min()
+    }
+
+    let mut seed = 42_usize;
+    let mut next = |max: usize| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) % max
+    };
+
+    for _ in 0..2000 {
+        let len = 1 + next(8);
+        let levels = (0..len).map(|_| next(10)).collect::<Vec<_>>();
+        let rules = Rules {
+            max_removals: next(4),
+            steps: 1..=1 + next(3),
+        };
+        assert_eq!(
+            rules.removals(&levels).map(|removed| removed.len()),
+            brute_force(&rules, &levels),
+            "{levels:?} {rules:?}"
+        );
+
+        if let Some(removed) = rules.removals(&levels) {
+            let kept = (0..len)
+                .filter(|i| !removed.contains(i))
+                .map(|i| levels[i])
+                .collect::<Vec<_>>();
+            assert!(removed.len() <= rules.max_removals);
+            assert_eq!(rules.diagnose(&kept), None, "{levels:?} {rules:?}");
+        }
+    }
+
+    assert_eq!(Rules::new(3).removals(&[1, 2, 3]), Some(vec![]));
+    assert_eq!(Rules::new(3).removals(&[1, 2, 2]), Some(vec![2]));
+    assert_eq!(Rules::new(1).removals(&[]), Some(vec![]));
+}
+
+#[test]
+fn test_explain() {
+    let input = include_str!("input1_test.txt");
+    let mut out = vec![];
+    Rules::new(1).explain(input, &mut out).unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "\
+7 6 4 2 1: safe
+1 2 7 8 9: unsafe at index 2, 2 -> 7 changes by 5, allowed 1..=3; can't be fixed with 1 removal(s)
+9 7 6 2 1: unsafe at index 3, 6 -> 2 changes by 4, allowed 1..=3; can't be fixed with 1 removal(s)
+1 3 2 4 5: unsafe at index 2, 3 -> 2 goes Dec after starting Inc; safe after removing index 2 (2)
+8 6 4 4 1: unsafe at index 3, 4 -> 4 neither increases nor decreases; safe after removing index 3 (4)
+1 3 6 7 9: safe
+"
+    );
+}
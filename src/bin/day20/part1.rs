@@ -1,3 +1,4 @@
+use adventofcode2024::inspect::Report;
 use itertools::Itertools;
 use std::collections::HashMap;
 
@@ -109,11 +110,19 @@ impl Matrix {
     fn get(&self, loc: Location) -> Cell {
         *self.data.get(loc.row).unwrap().get(loc.col).unwrap()
     }
+
+    fn free_neighbours(&self, loc: Location) -> Vec<Location> {
+        [(-1, 0), (1, 0), (0, -1), (0, 1)]
+            .into_iter()
+            .filter_map(|(drow, dcol)| loc.add(drow, dcol, self.rows_count, self.cols_count))
+            .filter(|next| self.get(*next) == Cell::Free)
+            .collect()
+    }
 }
 
 const MAX_PATH: u32 = 50_000;
 
-fn find_all_fastest_paths_uncached(matrix: &Matrix, locations: &[Location]) -> Vec<Vec<u32>> {
+fn find_all_fastest_paths(matrix: &Matrix, locations: &[Location]) -> Vec<Vec<u32>> {
     let mut distance = vec![vec![MAX_PATH; locations.len()]; locations.len()];
 
     for (left_id, left) in locations.iter().enumerate() {
@@ -146,7 +155,66 @@ fn find_all_fastest_paths_uncached(matrix: &Matrix, locations: &[Location]) -> V
     distance
 }
 
-fn solve(input: &str) -> usize {
+pub fn inspect(input: &str) -> Report {
+    let mut report = Report::new();
+    let matrix = Matrix::parse(input);
+
+    let free = (0..matrix.rows_count)
+        .cartesian_product(0..matrix.cols_count)
+        .map(|(row, col)| Location { row, col })
+        .filter(|loc| matrix.get(*loc) == Cell::Free)
+        .collect::<Vec<_>>();
+
+    report.fact(
+        "grid",
+        format!("{}x{}", matrix.rows_count, matrix.cols_count),
+    );
+    report.fact("free cells", free.len());
+    report.fact("start", matrix.start);
+    report.fact("end", matrix.end);
+
+    report.check(
+        "every free cell has at most two free neighbours",
+        free.iter()
+            .find(|loc| matrix.free_neighbours(**loc).len() > 2)
+            .map(|loc| format!("{loc} is a fork")),
+    );
+
+    let mut track = vec![matrix.start];
+    let mut prev = None;
+    while *track.last().unwrap() != matrix.end && track.len() <= free.len() {
+        let loc = *track.last().unwrap();
+        let Some(next) = matrix
+            .free_neighbours(loc)
+            .into_iter()
+            .find(|next| Some(*next) != prev)
+        else {
+            break;
+        };
+        prev = Some(loc);
+        track.push(next);
+    }
+    report.fact("track length", track.len() - 1);
+    report.check(
+        "a single track leads from start to end",
+        (*track.last().unwrap() != matrix.end)
+            .then(|| format!("dead end at {}", track.last().unwrap())),
+    );
+    report.check(
+        "the track covers every free cell",
+        (track.len() != free.len()).then(|| {
+            format!(
+                "track has {} cells, grid has {} free cells",
+                track.len(),
+                free.len()
+            )
+        }),
+    );
+
+    report
+}
+
+pub fn solve(input: &str) -> usize {
     let win_to_count = build_win_to_count_map(input);
 
     let mut out = 0;
@@ -158,59 +226,6 @@ fn solve(input: &str) -> usize {
     out
 }
 
-struct FsCache;
-
-impl FsCache {
-    const FILENAME: &str = if cfg!(test) {
-        "cache.test"
-    } else {
-        "cache.release"
-    };
-
-    fn write(data: &[Vec<u32>]) {
-        let mut bytes = vec![];
-        for row in data {
-            for col in row {
-                for byte in col.to_le_bytes() {
-                    bytes.push(byte);
-                }
-            }
-        }
-        std::fs::write(Self::FILENAME, bytes).unwrap()
-    }
-
-    fn read(matrix_size: usize) -> Vec<Vec<u32>> {
-        let bytes = std::fs::read(Self::FILENAME).unwrap();
-        let mut out = vec![];
-
-        for batch_u8 in bytes.chunks(matrix_size * 4) {
-            let mut buf = Vec::with_capacity(matrix_size);
-            for four in batch_u8.chunks(4) {
-                buf.push(u32::from_le_bytes([four[0], four[1], four[2], four[3]]));
-            }
-            out.push(buf);
-        }
-
-        out
-    }
-
-    fn fetch(matrix_size: usize, f: impl Fn() -> Vec<Vec<u32>>) -> Vec<Vec<u32>> {
-        if std::fs::exists(Self::FILENAME).is_ok_and(|v| v) {
-            Self::read(matrix_size)
-        } else {
-            let data = f();
-            Self::write(&data);
-            data
-        }
-    }
-}
-
-fn find_all_fastest_paths(matrix: &Matrix, locations: &[Location]) -> Vec<Vec<u32>> {
-    FsCache::fetch(locations.len(), || {
-        find_all_fastest_paths_uncached(matrix, locations)
-    })
-}
-
 fn build_win_to_count_map(input: &str) -> HashMap<u32, usize> {
     let matrix = Matrix::parse(input);
 
@@ -299,3 +314,14 @@ fn test1() {
     let output = solve(input);
     assert_eq!(output, 0);
 }
+
+#[test]
+fn test_inspect() {
+    let input = include_str!("input_test.txt");
+    let report = inspect(input);
+    assert!(report.passed());
+
+    let input = input.replacen("#.#", "...", 1);
+    let report = inspect(&input);
+    assert!(!report.passed());
+}
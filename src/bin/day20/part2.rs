@@ -113,7 +113,7 @@ impl Matrix {
 
 const MAX_PATH: u32 = 50_000;
 
-fn find_all_fastest_paths_uncached(matrix: &Matrix, locations: &[Location]) -> Vec<Vec<u32>> {
+fn find_all_fastest_paths(matrix: &Matrix, locations: &[Location]) -> Vec<Vec<u32>> {
     let mut distance = vec![vec![MAX_PATH; locations.len()]; locations.len()];
 
     for (left_id, left) in locations.iter().enumerate() {
@@ -146,7 +146,7 @@ fn find_all_fastest_paths_uncached(matrix: &Matrix, locations: &[Location]) -> V
     distance
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let win_to_count = build_win_to_count_map(input);
 
     let mut out = 0;
@@ -158,59 +158,6 @@ fn solve(input: &str) -> usize {
     out
 }
 
-struct FsCache;
-
-impl FsCache {
-    const FILENAME: &str = if cfg!(test) {
-        "cache.test"
-    } else {
-        "cache.release"
-    };
-
-    fn write(data: &[Vec<u32>]) {
-        let mut bytes = vec![];
-        for row in data {
-            for col in row {
-                for byte in col.to_le_bytes() {
-                    bytes.push(byte);
-                }
-            }
-        }
-        std::fs::write(Self::FILENAME, bytes).unwrap()
-    }
-
-    fn read(matrix_size: usize) -> Vec<Vec<u32>> {
-        let bytes = std::fs::read(Self::FILENAME).unwrap();
-        let mut out = vec![];
-
-        for batch_u8 in bytes.chunks(matrix_size * 4) {
-            let mut buf = Vec::with_capacity(matrix_size);
-            for four in batch_u8.chunks(4) {
-                buf.push(u32::from_le_bytes([four[0], four[1], four[2], four[3]]));
-            }
-            out.push(buf);
-        }
-
-        out
-    }
-
-    fn fetch(matrix_size: usize, f: impl Fn() -> Vec<Vec<u32>>) -> Vec<Vec<u32>> {
-        if std::fs::exists(Self::FILENAME).is_ok_and(|v| v) {
-            Self::read(matrix_size)
-        } else {
-            let data = f();
-            Self::write(&data);
-            data
-        }
-    }
-}
-
-fn find_all_fastest_paths(matrix: &Matrix, locations: &[Location]) -> Vec<Vec<u32>> {
-    FsCache::fetch(locations.len(), || {
-        find_all_fastest_paths_uncached(matrix, locations)
-    })
-}
-
 fn build_win_to_count_map(input: &str) -> HashMap<u32, usize> {
     let matrix = Matrix::parse(input);
 
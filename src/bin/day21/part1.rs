@@ -61,7 +61,7 @@ impl Code {
     }
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let codes = input.trim().lines().map(Code::new).collect::<Vec<_>>();
     let mut out = 0;
 
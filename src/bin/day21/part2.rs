@@ -125,7 +125,7 @@ impl RobotCode {
     }
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let codes = input.trim().lines().map(Code::new).collect::<Vec<_>>();
     let mut out = 0;
 
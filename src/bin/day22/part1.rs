@@ -27,7 +27,7 @@ impl Secret {
     }
 }
 
-fn solve(input: &str) -> u64 {
+pub fn solve(input: &str) -> u64 {
     let mut secrets = input
         .trim()
         .lines()
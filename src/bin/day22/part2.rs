@@ -91,7 +91,7 @@ fn index_prices(prices: &[Price], changes: &[Option<isize>]) -> SeqToFirstPrice
 #[derive(Debug, Clone, Copy)]
 struct Price(u64);
 
-fn solve(input: &str) -> u64 {
+pub fn solve(input: &str) -> u64 {
     let secrets = input
         .trim()
         .lines()
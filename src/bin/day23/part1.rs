@@ -8,7 +8,7 @@ fn main() {
     println!("{}", output);
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let input = input.trim();
     let mut edges = HashSet::<(&str, &str)>::new();
     let mut nodes = HashSet::<&str>::new();
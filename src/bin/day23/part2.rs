@@ -73,7 +73,7 @@ impl<'a> Pool<'a> {
     }
 }
 
-fn solve(input: &str) -> String {
+pub fn solve(input: &str) -> String {
     let input = input.trim();
 
     let mut pool = Pool::new();
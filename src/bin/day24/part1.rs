@@ -1,3 +1,4 @@
+use adventofcode2024::{inspect::Report, repl::Repl};
 use std::collections::{HashMap, HashSet};
 
 fn main() {
@@ -24,11 +25,14 @@ impl<'a> Pool<'a> {
         }
     }
 
-    #[allow(dead_code)]
     fn get(&self, n: usize) -> &'a str {
         self.inner.get(n).unwrap()
     }
 
+    fn find(&self, s: &str) -> Option<usize> {
+        self.inner.iter().position(|e| *e == s)
+    }
+
     fn zs(&self) -> Vec<(usize, &'a str)> {
         let mut out = self
             .inner
@@ -97,6 +101,14 @@ impl Gate {
             Gate::Xor { out, .. } => out,
         }
     }
+
+    fn op(self) -> &'static str {
+        match self {
+            Gate::And { .. } => "AND",
+            Gate::Or { .. } => "OR",
+            Gate::Xor { .. } => "XOR",
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -182,7 +194,152 @@ impl System {
     }
 }
 
-fn solve(input: &str) -> u64 {
+struct SystemRepl<'a> {
+    pool: Pool<'a>,
+    gates: Vec<Gate>,
+    wires: HashMap<usize, bool>,
+}
+
+impl SystemRepl<'_> {
+    fn describe(&self, wire: usize, depth: usize, indent: usize, out: &mut String) {
+        let value = u8::from(*self.wires.get(&wire).unwrap());
+        let name = self.pool.get(wire);
+        let gate = self.gates.iter().find(|gate| gate.out() == wire);
+
+        out.push_str(&" ".repeat(indent));
+        match gate {
+            Some(gate) => {
+                out.push_str(&format!(
+                    "{name} = {} {} {} = {value}\n",
+                    self.pool.get(gate.lhs()),
+                    gate.op(),
+                    self.pool.get(gate.rhs())
+                ));
+                if depth > 1 {
+                    self.describe(gate.lhs(), depth - 1, indent + 2, out);
+                    self.describe(gate.rhs(), depth - 1, indent + 2, out);
+                }
+            }
+            None => out.push_str(&format!("{name} = {value} (input)\n")),
+        }
+    }
+}
+
+impl Repl for SystemRepl<'_> {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            (
+                "wire <name> [depth]",
+                "print the value of a wire and the gates behind it",
+            ),
+            ("zs", "print all z wires and the number they form"),
+        ]
+    }
+
+    fn exec(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "wire" => {
+                let (name, depth) = match args {
+                    [name] => (*name, 1),
+                    [name, depth] => (
+                        *name,
+                        depth
+                            .parse()
+                            .map_err(|_| format!("invalid depth {depth:?}"))?,
+                    ),
+                    _ => return Err(String::from("expected a wire name")),
+                };
+                let wire = self
+                    .pool
+                    .find(name)
+                    .ok_or_else(|| format!("unknown wire {name:?}"))?;
+                let mut out = String::new();
+                self.describe(wire, depth, 0, &mut out);
+                Ok(out)
+            }
+            "zs" => {
+                let mut out = String::new();
+                let mut bits = String::new();
+                for (idx, wire) in self.pool.zs() {
+                    let value = u8::from(*self.wires.get(&idx).unwrap());
+                    out.push_str(&format!("{wire}: {value}\n"));
+                    bits.push_str(&value.to_string());
+                }
+                out.push_str(&format!("= {}", u64::from_str_radix(&bits, 2).unwrap()));
+                Ok(out)
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+pub fn repl(input: &str) -> Box<dyn Repl + '_> {
+    let mut pool = Pool::new();
+    let mut system = System::parse(input, &mut pool);
+    let gates = system.gates.iter().copied().collect();
+    system.fill();
+
+    Box::new(SystemRepl {
+        pool,
+        gates,
+        wires: system.wires,
+    })
+}
+
+pub fn inspect(input: &str) -> Report {
+    let mut report = Report::new();
+    let mut pool = Pool::new();
+    let system = System::parse(input, &mut pool);
+
+    let count_prefixed = |prefix: char| {
+        system
+            .all_known_wires
+            .iter()
+            .filter(|wire| pool.get(**wire).starts_with(prefix))
+            .count()
+    };
+    let xs = count_prefixed('x');
+    let ys = count_prefixed('y');
+    let zs = count_prefixed('z');
+    let count_op = |op: &str| system.gates.iter().filter(|gate| gate.op() == op).count();
+
+    report.fact("wires", system.all_known_wires.len());
+    report.fact("x/y/z wires", format!("{xs}/{ys}/{zs}"));
+    report.fact(
+        "gates (AND/OR/XOR)",
+        format!(
+            "{} ({}/{}/{})",
+            system.gates.len(),
+            count_op("AND"),
+            count_op("OR"),
+            count_op("XOR")
+        ),
+    );
+
+    report.check(
+        "x and y have the same width",
+        (xs != ys).then(|| format!("{xs} x wires, {ys} y wires")),
+    );
+    report.check(
+        "z is one bit wider than x",
+        (zs != xs + 1).then(|| format!("{xs} x wires, {zs} z wires")),
+    );
+    // one half adder (2 gates) for the lowest bit and full adders (5 gates) for the rest
+    let expected_gates = (5 * xs).saturating_sub(3);
+    report.check(
+        "gate count matches a ripple-carry adder",
+        (system.gates.len() != expected_gates).then(|| {
+            format!(
+                "expected {expected_gates} gates for {xs} bits, got {}",
+                system.gates.len()
+            )
+        }),
+    );
+
+    report
+}
+
+pub fn solve(input: &str) -> u64 {
     let mut pool = Pool::new();
     let mut system = System::parse(input, &mut pool);
     system.fill();
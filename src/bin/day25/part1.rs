@@ -1,3 +1,4 @@
+use adventofcode2024::{inspect::Report, repl::Repl};
 use std::collections::HashSet;
 
 fn main() {
@@ -14,28 +15,71 @@ struct LocksAndKeys {
     keys: HashSet<Seq>,
 }
 
-fn parse_rectangle(input: &str) -> (usize, usize, Vec<Vec<bool>>) {
+fn parse_rectangle(input: &str) -> Result<(usize, usize, Vec<Vec<bool>>), String> {
     let mut out: Vec<Vec<bool>> = vec![];
     for row in input.lines() {
         let row_as_marks = row
             .bytes()
-            .map(|b| {
-                assert!(b == b'#' || b == b'.');
-                b == b'#'
+            .map(|b| match b {
+                b'#' => Ok(true),
+                b'.' => Ok(false),
+                _ => Err(format!("unexpected {:?}", b as char)),
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>()?;
         out.push(row_as_marks);
     }
 
     let rows_count = out.len();
-    let estimated_cols_count = out[0].len();
+    let estimated_cols_count = out.first().map_or(0, |row| row.len());
 
-    for row in out.iter() {
-        assert_eq!(row.len(), estimated_cols_count);
+    if let Some(row) = out.iter().find(|row| row.len() != estimated_cols_count) {
+        return Err(format!(
+            "a row is {} wide, the first one is {estimated_cols_count}",
+            row.len()
+        ));
     }
     let cols_count = estimated_cols_count;
 
-    (rows_count, cols_count, out)
+    Ok((rows_count, cols_count, out))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Schematic {
+    Lock(Seq),
+    Key(Seq),
+}
+
+/// Parses a 5x7 schematic of `#` and `.`, locks are filled on top and keys at the bottom.
+fn parse_schematic(input: &str) -> Result<Schematic, String> {
+    let (rows, cols, matrix) = parse_rectangle(input)?;
+    if cols != 5 {
+        return Err(format!("{cols} columns, expected 5"));
+    }
+    if rows != 7 {
+        return Err(format!("{rows} rows, expected 7"));
+    }
+
+    let height = |col: usize, rows: &mut dyn Iterator<Item = usize>| {
+        let filled = rows.take_while(|row| matrix[*row][col]).count();
+        filled as u8 - 1
+    };
+    let mut heights = [0; 5];
+
+    if matrix[0].iter().all(|filled| *filled) {
+        // goes down, it's a lock
+        for (col, h) in heights.iter_mut().enumerate() {
+            *h = height(col, &mut (0..rows));
+        }
+        Ok(Schematic::Lock(heights))
+    } else if matrix[rows - 1].iter().all(|filled| *filled) {
+        // goes up, it's a key
+        for (col, h) in heights.iter_mut().enumerate() {
+            *h = height(col, &mut (0..rows).rev());
+        }
+        Ok(Schematic::Key(heights))
+    } else {
+        Err(String::from("neither top nor bottom row is filled"))
+    }
 }
 
 impl LocksAndKeys {
@@ -43,50 +87,18 @@ impl LocksAndKeys {
         let mut locks = HashSet::new();
         let mut keys = HashSet::new();
 
-        for case in input.split("\n\n") {
-            let (rows, cols, matrix) = parse_rectangle(case);
-            assert_eq!(cols, 5);
-
-            // just to make sure we don't parse garbage for part 2
-            if matrix[0][0] {
-                // goes down, it's a lock
-                let mut heights = [0; 5];
-                #[allow(clippy::needless_range_loop)]
-                for col in 0..cols {
-                    let mut height = 0_u8;
-                    for row in 0..rows {
-                        if matrix[row][col] {
-                            height += 1;
-                        } else {
-                            break;
-                        }
-                    }
-                    heights[col] = height.checked_sub(1).unwrap();
-                }
-                locks.insert(heights);
-            } else {
-                // goes up, it's a key
-                let mut heights = [0; 5];
-                #[allow(clippy::needless_range_loop)]
-                for col in 0..cols {
-                    let mut height = 0_u8;
-                    for row in (0..rows).rev() {
-                        if matrix[row][col] {
-                            height += 1;
-                        } else {
-                            break;
-                        }
-                    }
-                    heights[col] = height.checked_sub(1).unwrap();
-                }
-                keys.insert(heights);
+        for case in input.trim().split("\n\n") {
+            match parse_schematic(case) {
+                Ok(Schematic::Lock(heights)) => locks.insert(heights),
+                Ok(Schematic::Key(heights)) => keys.insert(heights),
+                Err(err) => panic!("invalid schematic: {err}\n{case}"),
             };
         }
 
         Self { locks, keys }
     }
 
-    fn find_pairs(self) -> Vec<(Seq, Seq)> {
+    fn find_pairs(&self) -> Vec<(Seq, Seq)> {
         let mut out = vec![];
 
         for lock in self.locks.iter() {
@@ -102,9 +114,84 @@ impl LocksAndKeys {
     }
 }
 
-fn solve(input: &str) -> u64 {
+fn sorted(seqs: impl IntoIterator<Item = Seq>) -> Vec<Seq> {
+    let mut out = seqs.into_iter().collect::<Vec<_>>();
+    out.sort_unstable();
+    out
+}
+
+impl Repl for LocksAndKeys {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("locks", "print pin heights of all locks"),
+            ("keys", "print heights of all keys"),
+            ("pairs", "print all lock/key pairs that fit"),
+        ]
+    }
+
+    fn exec(&mut self, command: &str, _args: &[&str]) -> Result<String, String> {
+        let lines = match command {
+            "locks" => sorted(self.locks.iter().copied())
+                .into_iter()
+                .map(|lock| format!("{lock:?}"))
+                .collect::<Vec<_>>(),
+            "keys" => sorted(self.keys.iter().copied())
+                .into_iter()
+                .map(|key| format!("{key:?}"))
+                .collect(),
+            "pairs" => {
+                let mut pairs = self.find_pairs();
+                pairs.sort_unstable();
+                pairs
+                    .into_iter()
+                    .map(|(lock, key)| format!("lock {lock:?} fits key {key:?}"))
+                    .collect()
+            }
+            _ => unreachable!(),
+        };
+        Ok(lines.join("\n"))
+    }
+}
+
+pub fn inspect(input: &str) -> Report {
+    let mut report = Report::new();
+    let schematics = input
+        .trim()
+        .split("\n\n")
+        .map(parse_schematic)
+        .collect::<Vec<_>>();
+
+    let locks = schematics
+        .iter()
+        .filter(|s| matches!(s, Ok(Schematic::Lock(_))))
+        .count();
+    let keys = schematics
+        .iter()
+        .filter(|s| matches!(s, Ok(Schematic::Key(_))))
+        .count();
+
+    report.fact("schematics", schematics.len());
+    report.fact("locks", locks);
+    report.fact("keys", keys);
+
+    report.check(
+        "every schematic is a 5x7 lock or key of # and .",
+        schematics.iter().enumerate().find_map(|(idx, s)| {
+            s.as_ref()
+                .err()
+                .map(|err| format!("schematic {}: {err}", idx + 1))
+        }),
+    );
+
+    report
+}
+
+pub fn repl(input: &str) -> Box<dyn Repl + '_> {
+    Box::new(LocksAndKeys::parse(input))
+}
+
+pub fn solve(input: &str) -> u64 {
     let locks_and_keys = LocksAndKeys::parse(input);
-    println!("{:?}", locks_and_keys);
 
     let matches = locks_and_keys.find_pairs();
 
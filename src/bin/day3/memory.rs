@@ -0,0 +1,273 @@
+use std::io::Read;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ins {
+    Mul { lhs: usize, rhs: usize },
+    Do,
+    Dont,
+}
+
+/// Grammar of a single instruction: `name(arg,arg,...)` with 1-3 digit arguments.
+pub struct InsKind {
+    name: &'static [u8],
+    arity: usize,
+    build: fn(&[usize]) -> Ins,
+}
+
+pub const INSTRUCTIONS: &[InsKind] = &[
+    InsKind {
+        name: b"mul",
+        arity: 2,
+        build: |args| Ins::Mul {
+            lhs: args[0],
+            rhs: args[1],
+        },
+    },
+    InsKind {
+        name: b"do",
+        arity: 0,
+        build: |_| Ins::Do,
+    },
+    InsKind {
+        name: b"don't",
+        arity: 0,
+        build: |_| Ins::Dont,
+    },
+];
+
+const MAX_DIGITS: usize = 3;
+/// Largest arity in `INSTRUCTIONS`, so that arguments of any instruction fit on the stack.
+const MAX_ARITY: usize = {
+    let mut max = 0;
+    let mut idx = 0;
+    while idx < INSTRUCTIONS.len() {
+        if INSTRUCTIONS[idx].arity > max {
+            max = INSTRUCTIONS[idx].arity;
+        }
+        idx += 1;
+    }
+    max
+};
+
+enum Match {
+    Found(Ins, usize),
+    /// Input ends in the middle of what may still become an instruction.
+    Partial,
+    None,
+}
+
+impl InsKind {
+    /// Matches an instruction at the beginning of `input`,
+    /// `Found` carries the number of consumed bytes.
+    fn match_at(&self, input: &[u8]) -> Match {
+        let mut pos = 0;
+        for expected in self.name.iter().chain(b"(") {
+            match input.get(pos) {
+                None => return Match::Partial,
+                Some(b) if b == expected => pos += 1,
+                Some(_) => return Match::None,
+            }
+        }
+
+        let mut args = [0; MAX_ARITY];
+        for (idx, arg) in args[..self.arity].iter_mut().enumerate() {
+            if idx > 0 {
+                match input.get(pos) {
+                    None => return Match::Partial,
+                    Some(b',') => pos += 1,
+                    Some(_) => return Match::None,
+                }
+            }
+
+            let digits = input[pos..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count();
+            if pos + digits == input.len() && digits <= MAX_DIGITS {
+                return Match::Partial;
+            }
+            if !(1..=MAX_DIGITS).contains(&digits) {
+                return Match::None;
+            }
+            for b in &input[pos..pos + digits] {
+                *arg = *arg * 10 + usize::from(b - b'0');
+            }
+            pos += digits;
+        }
+
+        match input.get(pos) {
+            None => Match::Partial,
+            Some(b')') => Match::Found((self.build)(&args[..self.arity]), pos + 1),
+            Some(_) => Match::None,
+        }
+    }
+}
+
+/// Tries every instruction kind at the beginning of `input`.
+fn match_any(input: &[u8]) -> Match {
+    let mut partial = false;
+    for kind in INSTRUCTIONS {
+        match kind.match_at(input) {
+            Match::Found(ins, len) => return Match::Found(ins, len),
+            Match::Partial => partial = true,
+            Match::None => {}
+        }
+    }
+    if partial {
+        Match::Partial
+    } else {
+        Match::None
+    }
+}
+
+/// Scans corrupted memory once, yielding every well-formed instruction.
+pub struct Lexer<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input: input.as_bytes(),
+            pos: 0,
+        }
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Ins;
+
+    fn next(&mut self) -> Option<Ins> {
+        while self.pos < self.input.len() {
+            match match_any(&self.input[self.pos..]) {
+                Match::Found(ins, len) => {
+                    self.pos += len;
+                    return Some(ins);
+                }
+                Match::Partial | Match::None => self.pos += 1,
+            }
+        }
+        None
+    }
+}
+
+/// Same as `Lexer`, but reads memory in chunks of `chunk_len` bytes,
+/// only keeping an unfinished instruction between chunks.
+pub struct Scanner<R> {
+    reader: R,
+    chunk_len: usize,
+    buf: Vec<u8>,
+    pos: usize,
+    eof: bool,
+}
+
+impl<R: Read> Scanner<R> {
+    pub fn new(reader: R, chunk_len: usize) -> Self {
+        assert!(chunk_len > 0);
+        Self {
+            reader,
+            chunk_len,
+            buf: vec![],
+            pos: 0,
+            eof: false,
+        }
+    }
+
+    fn refill(&mut self) {
+        self.buf.drain(..self.pos);
+        self.pos = 0;
+
+        let len = self.buf.len();
+        self.buf.resize(len + self.chunk_len, 0);
+        let read = loop {
+            match self.reader.read(&mut self.buf[len..]) {
+                Ok(read) => break read,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(err) => panic!("failed to read memory: {err}"),
+            }
+        };
+        self.buf.truncate(len + read);
+        self.eof = read == 0;
+    }
+}
+
+impl<R: Read> Iterator for Scanner<R> {
+    type Item = Ins;
+
+    fn next(&mut self) -> Option<Ins> {
+        loop {
+            if self.pos == self.buf.len() {
+                if self.eof {
+                    return None;
+                }
+                self.refill();
+                continue;
+            }
+
+            match match_any(&self.buf[self.pos..]) {
+                Match::Found(ins, len) => {
+                    self.pos += len;
+                    return Some(ins);
+                }
+                Match::Partial if !self.eof => self.refill(),
+                Match::Partial | Match::None => self.pos += 1,
+            }
+        }
+    }
+}
+
+pub struct Interpreter {
+    /// Whether `do()`/`don't()` switch `mul` on and off.
+    conditionals: bool,
+    enabled: bool,
+    pub sum: usize,
+}
+
+impl Interpreter {
+    pub fn new(conditionals: bool) -> Self {
+        Self {
+            conditionals,
+            enabled: true,
+            sum: 0,
+        }
+    }
+
+    pub fn exec(&mut self, ins: Ins) {
+        match ins {
+            Ins::Mul { lhs, rhs } if self.enabled => self.sum += lhs * rhs,
+            Ins::Do if self.conditionals => self.enabled = true,
+            Ins::Dont if self.conditionals => self.enabled = false,
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn test_lexer() {
+    let insns = Lexer::new("mul(1,22)mul(333,4)mul(4444,5)mul(,1)mul(1,2 do()don't()do(1)")
+        .collect::<Vec<_>>();
+    assert_eq!(
+        insns,
+        [
+            Ins::Mul { lhs: 1, rhs: 22 },
+            Ins::Mul { lhs: 333, rhs: 4 },
+            Ins::Do,
+            Ins::Dont
+        ]
+    );
+
+    let insns = Lexer::new("mul(mul(2,3)").collect::<Vec<_>>();
+    assert_eq!(insns, [Ins::Mul { lhs: 2, rhs: 3 }]);
+}
+
+#[test]
+fn test_scanner() {
+    let input = "mul(1,22)mul(333,4)mul(4444,5)mul(,1)mul(1,2 do()don't()do(1)mul(mul(2,3)mul(7,8";
+    let expected = Lexer::new(input).collect::<Vec<_>>();
+
+    for chunk_len in 1..=input.len() + 1 {
+        let insns = Scanner::new(input.as_bytes(), chunk_len).collect::<Vec<_>>();
+        assert_eq!(insns, expected, "chunk_len = {chunk_len}");
+    }
+}
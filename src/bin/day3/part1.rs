@@ -1,23 +1,40 @@
+#[path = "memory.rs"]
+mod memory;
+
+use memory::{Interpreter, Lexer, Scanner};
+use std::io::Read;
+
+const CHUNK_LEN: usize = 64 * 1024;
+
 fn main() {
-    let input = include_str!("input.txt");
-    let output = solve(input);
+    let output = match std::env::args().nth(1).as_deref() {
+        Some("-") => solve_streaming(std::io::stdin().lock(), CHUNK_LEN),
+        Some(path) => solve_streaming(std::fs::File::open(path).unwrap(), CHUNK_LEN),
+        None => solve(include_str!("input.txt")),
+    };
     println!("{}", output);
 }
 
-fn solve(input: &str) -> usize {
-    let re = regex::Regex::new(r#"mul\((\d+),(\d+)\)"#).unwrap();
-    re.captures_iter(input)
-        .map(|c| {
-            let lhs = c.get(1).unwrap().as_str().parse::<usize>().unwrap();
-            let rhs = c.get(2).unwrap().as_str().parse::<usize>().unwrap();
-            lhs * rhs
-        })
-        .sum()
+pub fn solve(input: &str) -> usize {
+    let mut interpreter = Interpreter::new(false);
+    for ins in Lexer::new(input) {
+        interpreter.exec(ins);
+    }
+    interpreter.sum
+}
+
+pub fn solve_streaming(reader: impl Read, chunk_len: usize) -> usize {
+    let mut interpreter = Interpreter::new(false);
+    for ins in Scanner::new(reader, chunk_len) {
+        interpreter.exec(ins);
+    }
+    interpreter.sum
 }
 
 #[test]
 fn test() {
     let input = include_str!("input1_test.txt");
     let output = solve(input);
+    assert_eq!(solve_streaming(input.as_bytes(), 7), output);
     assert_eq!(output, 161);
 }
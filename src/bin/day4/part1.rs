@@ -1,10 +1,47 @@
+use adventofcode2024::args::take_flag;
+use aho_corasick::AhoCorasick;
+use crossterm::style::{Color, Stylize};
+
 fn main() {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let wraparound = take_flag(&mut args, "--wrap");
+    let render_matches = take_flag(&mut args, "--render");
+    let color = take_flag(&mut args, "--color");
     let input = include_bytes!("input.txt");
-    let output = solve(input);
-    println!("{}", output);
+
+    if args.is_empty() && !render_matches {
+        let output = solve(input);
+        println!("{}", output);
+        return;
+    }
+
+    if args.is_empty() {
+        args.push(String::from("XMAS"));
+    }
+    let words = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let search = WordSearch::new(&words).with_wraparound(wraparound);
+    let matrix = Matrix::parse(input);
+    let found = search.run(&matrix);
+
+    if render_matches {
+        print!("{}", render(&matrix, &found.matches, color));
+        return;
+    }
+
+    for (word, count) in words.iter().zip(&found.counts) {
+        println!("{word}: {count}");
+    }
+    for m in &found.matches {
+        let cells = m
+            .cells
+            .iter()
+            .map(|p| format!("({},{})", p.row, p.col))
+            .collect::<Vec<_>>();
+        println!("{} {:?} {}", words[m.word], m.dir, cells.join(" "));
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Direction {
     Right,
     RightBottom,
@@ -30,22 +67,34 @@ impl Direction {
         ]
     }
 
-    fn apply(self, start: Point) -> Word {
-        let base = Word([start, start, start, start]);
+    fn color(self) -> Color {
+        match self {
+            Direction::Right => Color::Red,
+            Direction::RightBottom => Color::Green,
+            Direction::Bottom => Color::Yellow,
+            Direction::LeftBottom => Color::Blue,
+            Direction::Left => Color::Magenta,
+            Direction::LeftTop => Color::Cyan,
+            Direction::Top => Color::DarkYellow,
+            Direction::RightTop => Color::DarkMagenta,
+        }
+    }
+
+    fn delta(self) -> (isize, isize) {
         match self {
-            Direction::Right => base.add0(0, 1),
-            Direction::RightBottom => base.add0(1, 1),
-            Direction::Bottom => base.add0(1, 0),
-            Direction::LeftBottom => base.add0(1, -1),
-            Direction::Left => base.add0(0, -1),
-            Direction::LeftTop => base.add0(-1, -1),
-            Direction::Top => base.add0(-1, 0),
-            Direction::RightTop => base.add0(-1, 1),
+            Direction::Right => (0, 1),
+            Direction::RightBottom => (1, 1),
+            Direction::Bottom => (1, 0),
+            Direction::LeftBottom => (1, -1),
+            Direction::Left => (0, -1),
+            Direction::LeftTop => (-1, -1),
+            Direction::Top => (-1, 0),
+            Direction::RightTop => (-1, 1),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Point {
     row: isize,
     col: isize,
@@ -59,53 +108,9 @@ impl Point {
         }
     }
 
-    fn materialize(self, matrix: &Matrix<'_>) -> Option<u8> {
-        if self.row < 0 || self.col < 0 {
-            return None;
-        }
-        let row = self.row as usize;
-        let col = self.col as usize;
-        matrix.lines.get(row)?.get(col).copied()
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-struct Word([Point; 4]);
-
-impl Word {
-    fn add(
-        self,
-        d1: (isize, isize),
-        d2: (isize, isize),
-        d3: (isize, isize),
-        d4: (isize, isize),
-    ) -> Self {
-        let [p1, p2, p3, p4] = self.0;
-        Self([
-            p1.add(d1.0, d1.1),
-            p2.add(d2.0, d2.1),
-            p3.add(d3.0, d3.1),
-            p4.add(d4.0, d4.1),
-        ])
-    }
-
-    fn add0(self, drow: isize, dcol: isize) -> Self {
-        self.add(
-            (0, 0),
-            (drow, dcol),
-            (drow * 2, dcol * 2),
-            (drow * 3, dcol * 3),
-        )
-    }
-
-    fn materialize(self, matrix: &Matrix<'_>) -> Option<[u8; 4]> {
-        let [p1, p2, p3, p4] = self.0;
-        Some([
-            p1.materialize(matrix)?,
-            p2.materialize(matrix)?,
-            p3.materialize(matrix)?,
-            p4.materialize(matrix)?,
-        ])
+    fn step(self, dir: Direction, n: usize) -> Self {
+        let (drow, dcol) = dir.delta();
+        self.add(drow * n as isize, dcol * n as isize)
     }
 }
 
@@ -116,38 +121,251 @@ struct Matrix<'a> {
     cols_count: usize,
 }
 
-fn solve(input: &[u8]) -> usize {
-    let mut out = 0;
+impl<'a> Matrix<'a> {
+    fn parse(input: &'a [u8]) -> Self {
+        let lines = input
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let cols_count = lines.first().map_or(0, |line| line.len());
+        assert!(
+            lines.iter().all(|line| line.len() == cols_count),
+            "grid is not rectangular"
+        );
+        Self {
+            rows_count: lines.len(),
+            cols_count,
+            lines,
+        }
+    }
 
-    let lines = input.split(|b| *b == b'\n').collect::<Vec<_>>();
-    let matrix = Matrix {
-        rows_count: lines.len(),
-        cols_count: lines[0].len(),
-        lines,
-    };
-    for row in 0..matrix.rows_count {
-        for col in 0..matrix.cols_count {
-            let start = Point {
+    fn contains(&self, point: Point) -> bool {
+        (0..self.rows_count as isize).contains(&point.row)
+            && (0..self.cols_count as isize).contains(&point.col)
+    }
+
+    fn wrap(&self, point: Point) -> Point {
+        Point {
+            row: point.row.rem_euclid(self.rows_count as isize),
+            col: point.col.rem_euclid(self.cols_count as isize),
+        }
+    }
+
+    fn get(&self, point: Point) -> u8 {
+        self.lines[point.row as usize][point.col as usize]
+    }
+
+    fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.rows_count).flat_map(move |row| {
+            (0..self.cols_count).map(move |col| Point {
                 row: row as isize,
                 col: col as isize,
-            };
-            for dir in Direction::all() {
-                let word = dir.apply(start);
-                if let Some(xmas) = word.materialize(&matrix) {
-                    if xmas == [b'X', b'M', b'A', b'S'] {
-                        out += 1
+            })
+        })
+    }
+}
+
+/// A straight line of `len` distinct cells, walked from `start` in one direction.
+/// With wraparound it's a cycle on the torus.
+#[derive(Debug, Clone, Copy)]
+struct Ray {
+    start: Point,
+    len: usize,
+}
+
+fn rays(matrix: &Matrix<'_>, dir: Direction, wraparound: bool) -> Vec<Ray> {
+    let mut out = vec![];
+
+    if wraparound {
+        let mut visited = vec![false; matrix.rows_count * matrix.cols_count];
+        for start in matrix.points() {
+            let mut point = start;
+            let mut len = 0;
+            loop {
+                let idx = point.row as usize * matrix.cols_count + point.col as usize;
+                if visited[idx] {
+                    break;
+                }
+                visited[idx] = true;
+                len += 1;
+                point = matrix.wrap(point.step(dir, 1));
+            }
+            if len > 0 {
+                out.push(Ray { start, len });
+            }
+        }
+    } else {
+        let (drow, dcol) = dir.delta();
+        for start in matrix.points() {
+            if matrix.contains(start.add(-drow, -dcol)) {
+                continue;
+            }
+            let len = (0..)
+                .take_while(|n| matrix.contains(start.step(dir, *n)))
+                .count();
+            out.push(Ray { start, len });
+        }
+    }
+
+    out
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Match {
+    /// Index of the word in the dictionary.
+    word: usize,
+    dir: Direction,
+    cells: Vec<Point>,
+}
+
+#[derive(Debug)]
+struct Found {
+    counts: Vec<usize>,
+    matches: Vec<Match>,
+}
+
+struct WordSearch {
+    lens: Vec<usize>,
+    automaton: AhoCorasick,
+    wraparound: bool,
+}
+
+impl WordSearch {
+    fn new(words: &[&str]) -> Self {
+        assert!(
+            words.iter().all(|word| !word.is_empty()),
+            "words can't be empty"
+        );
+        Self {
+            lens: words.iter().map(|word| word.len()).collect(),
+            automaton: AhoCorasick::new(words).unwrap(),
+            wraparound: false,
+        }
+    }
+
+    fn with_wraparound(mut self, wraparound: bool) -> Self {
+        self.wraparound = wraparound;
+        self
+    }
+
+    fn run(&self, matrix: &Matrix<'_>) -> Found {
+        let mut found = Found {
+            counts: vec![0; self.lens.len()],
+            matches: vec![],
+        };
+        // a cycle is extended so that words crossing its starting cell are found too
+        let extra = if self.wraparound {
+            self.lens.iter().max().map_or(0, |len| len - 1)
+        } else {
+            0
+        };
+
+        for dir in Direction::all() {
+            for ray in rays(matrix, dir, self.wraparound) {
+                let cell = |n| matrix.wrap(ray.start.step(dir, n));
+                let haystack = (0..ray.len + extra.min(ray.len - 1))
+                    .map(|n| matrix.get(cell(n)))
+                    .collect::<Vec<_>>();
+
+                for m in self.automaton.find_overlapping_iter(&haystack) {
+                    // a word longer than the cycle would read the same cells twice
+                    if m.start() >= ray.len || m.len() > ray.len {
+                        continue;
                     }
+                    let word = m.pattern().as_usize();
+                    found.counts[word] += 1;
+                    found.matches.push(Match {
+                        word,
+                        dir,
+                        cells: m.range().map(cell).collect(),
+                    });
                 }
             }
         }
+
+        found
+    }
+}
+
+/// Re-prints the grid with letters that aren't part of any match replaced by `.`,
+/// when `color` is set matched letters are coloured by the direction of the first match.
+fn render(matrix: &Matrix<'_>, matches: &[Match], color: bool) -> String {
+    let mut dirs = vec![None; matrix.rows_count * matrix.cols_count];
+    for m in matches {
+        for p in &m.cells {
+            dirs[p.row as usize * matrix.cols_count + p.col as usize].get_or_insert(m.dir);
+        }
     }
 
+    let mut out = String::new();
+    for (idx, point) in matrix.points().enumerate() {
+        match dirs[idx] {
+            Some(dir) if color => {
+                let b = matrix.get(point) as char;
+                out.push_str(&b.with(dir.color()).bold().to_string());
+            }
+            Some(_) => out.push(matrix.get(point) as char),
+            None => out.push('.'),
+        }
+        if point.col as usize == matrix.cols_count - 1 {
+            out.push('\n');
+        }
+    }
     out
 }
 
+pub fn solve(input: &[u8]) -> usize {
+    let matrix = Matrix::parse(input);
+    WordSearch::new(&["XMAS"]).run(&matrix).counts[0]
+}
+
 #[test]
 fn test() {
     let input = include_bytes!("input_test.txt");
     let output = solve(input);
     assert_eq!(output, 18);
 }
+
+#[test]
+fn test_word_search() {
+    let matrix = Matrix::parse(include_bytes!("input_test.txt"));
+    let found = WordSearch::new(&["XMAS", "SAMX", "MAS"]).run(&matrix);
+    assert_eq!(found.counts[0], 18);
+    assert_eq!(found.counts[1], 18);
+    assert_eq!(found.matches.len(), found.counts.iter().sum::<usize>());
+
+    let matrix = Matrix::parse(b"SXMA\n....\n");
+    let search = WordSearch::new(&["XMAS"]);
+    assert_eq!(search.run(&matrix).counts, [0]);
+    let found = search.with_wraparound(true).run(&matrix);
+    assert_eq!(
+        found.matches,
+        [Match {
+            word: 0,
+            dir: Direction::Right,
+            cells: [(0, 1), (0, 2), (0, 3), (0, 0)]
+                .map(|(row, col)| Point { row, col })
+                .to_vec()
+        }]
+    );
+
+    let matrix = Matrix::parse(b"XMA\n...\n");
+    let search = WordSearch::new(&["XMAX", "XMA"]).with_wraparound(true);
+    assert_eq!(search.run(&matrix).counts, [0, 1]);
+}
+
+#[test]
+fn test_render() {
+    let matrix = Matrix::parse(b"..X...\n.SAMX.\n.A..A.\nXMAS.S\n.X....\n");
+    let found = WordSearch::new(&["XMAS"]).run(&matrix);
+    assert_eq!(found.counts, [4]);
+    assert_eq!(
+        render(&matrix, &found.matches, false),
+        "..X...\n.SAMX.\n.A..A.\nXMAS.S\n.X....\n"
+    );
+
+    let matrix = Matrix::parse(b"XMASZ\nQQQQQ\n");
+    let found = WordSearch::new(&["XMAS"]).run(&matrix);
+    assert_eq!(render(&matrix, &found.matches, false), "XMAS.\n.....\n");
+    assert!(render(&matrix, &found.matches, true).contains("\u{1b}["));
+}
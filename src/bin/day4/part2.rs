@@ -1,10 +1,25 @@
 fn main() {
     let input = include_bytes!("input.txt");
-    let output = solve(input);
+    let output = match std::env::args().nth(1) {
+        Some(pattern) => {
+            let pattern = Pattern::parse(&pattern.replace('/', "\n"));
+            let matches = pattern.find_all(&Matrix::parse(input));
+            for m in &matches {
+                println!("variant {} at ({},{})", m.variant, m.at.row, m.at.col);
+            }
+            matches.len()
+        }
+        None => solve(input),
+    };
     println!("{}", output);
 }
 
-#[derive(Debug, Clone, Copy)]
+const X_MAS: &str = "\
+M.S
+.A.
+M.S";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 struct Point {
     row: isize,
     col: isize,
@@ -18,6 +33,20 @@ impl Point {
         }
     }
 
+    fn rotate(self) -> Self {
+        Self {
+            row: self.col,
+            col: -self.row,
+        }
+    }
+
+    fn reflect(self) -> Self {
+        Self {
+            row: self.row,
+            col: -self.col,
+        }
+    }
+
     fn materialize(self, matrix: &Matrix<'_>) -> Option<u8> {
         if self.row < 0 || self.col < 0 {
             return None;
@@ -28,56 +57,136 @@ impl Point {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Square(Point);
+/// Literal letters of a shape, `.` cells match anything and aren't stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Shape(Vec<(Point, u8)>);
 
-impl Square {
-    fn top_left(self) -> Point {
-        self.0
-    }
-    fn top_right(self) -> Point {
-        self.0.add(0, 2)
-    }
-    fn center(self) -> Point {
-        self.0.add(1, 1)
-    }
-    fn bottom_left(self) -> Point {
-        self.0.add(2, 0)
-    }
-    fn bottom_right(self) -> Point {
-        self.0.add(2, 2)
+impl Shape {
+    /// Moves cells so that their bounding box starts at (0,0),
+    /// returns the shape and the former top-left corner of the box.
+    fn normalize(cells: impl Iterator<Item = (Point, u8)>) -> (Self, Point) {
+        let cells = cells.collect::<Vec<_>>();
+        let min = Point {
+            row: cells.iter().map(|(p, _)| p.row).min().unwrap_or(0),
+            col: cells.iter().map(|(p, _)| p.col).min().unwrap_or(0),
+        };
+        let mut cells = cells
+            .into_iter()
+            .map(|(p, b)| (p.add(-min.row, -min.col), b))
+            .collect::<Vec<_>>();
+        cells.sort_unstable();
+        (Self(cells), min)
     }
 
-    fn diags(self) -> [Diag; 2] {
-        [
-            Diag([self.top_left(), self.center(), self.bottom_right()]),
-            Diag([self.top_right(), self.center(), self.bottom_left()]),
-        ]
+    fn matches_at(&self, matrix: &Matrix<'_>, at: Point) -> bool {
+        self.0
+            .iter()
+            .all(|(p, b)| p.add(at.row, at.col).materialize(matrix) == Some(*b))
     }
-    fn matches(self, matrix: &Matrix<'_>) -> bool {
-        let [diag1, diag2] = self.diags();
+}
 
-        diag1.matches(matrix) && diag2.matches(matrix)
-    }
+/// A 2D pattern matched under all rotations and reflections.
+#[derive(Debug)]
+struct Pattern {
+    variants: Vec<Shape>,
+    /// Top-left corner of the whole pattern, `.` cells included,
+    /// relative to the bounding box of each variant's letters.
+    origins: Vec<Point>,
+    /// Far corner of the whole pattern relative to its top-left one, per variant.
+    extents: Vec<Point>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Match {
+    /// Index of the matched rotation/reflection in `Pattern::variants`.
+    variant: usize,
+    /// Top-left corner of the rotated/reflected pattern, `.` cells included.
+    at: Point,
 }
 
-struct Diag([Point; 3]);
+impl Pattern {
+    fn parse(pattern: &str) -> Self {
+        let mut cells = vec![];
+        let mut far_corner = Point { row: 0, col: 0 };
+        for (row, line) in pattern.lines().enumerate() {
+            for (col, b) in line.bytes().enumerate() {
+                let point = Point {
+                    row: row as isize,
+                    col: col as isize,
+                };
+                far_corner.row = far_corner.row.max(point.row);
+                far_corner.col = far_corner.col.max(point.col);
+                if b != b'.' {
+                    cells.push((point, b));
+                }
+            }
+        }
+        assert!(!cells.is_empty(), "pattern has no letters");
 
-impl Diag {
-    fn materialize(self, matrix: &Matrix<'_>) -> Option<[u8; 3]> {
-        let [p1, p2, p3] = self.0;
-        Some([
-            p1.materialize(matrix)?,
-            p2.materialize(matrix)?,
-            p3.materialize(matrix)?,
-        ])
+        let mut corners = [Point { row: 0, col: 0 }, far_corner];
+        let mut variants: Vec<Shape> = vec![];
+        let mut origins = vec![];
+        let mut extents = vec![];
+        for _ in 0..4 {
+            for transform in [|p| p, Point::reflect] {
+                let (variant, min) =
+                    Shape::normalize(cells.iter().map(|(p, b)| (transform(*p), *b)));
+                // symmetric patterns would otherwise match the same cells twice
+                if !variants.contains(&variant) {
+                    let [a, b] = corners.map(transform);
+                    origins.push(Point {
+                        row: a.row.min(b.row) - min.row,
+                        col: a.col.min(b.col) - min.col,
+                    });
+                    extents.push(Point {
+                        row: a.row.abs_diff(b.row) as isize,
+                        col: a.col.abs_diff(b.col) as isize,
+                    });
+                    variants.push(variant);
+                }
+            }
+            cells = cells.into_iter().map(|(p, b)| (p.rotate(), b)).collect();
+            corners = corners.map(Point::rotate);
+        }
+
+        Self {
+            variants,
+            origins,
+            extents,
+        }
     }
 
-    fn matches(self, matrix: &Matrix<'_>) -> bool {
-        if let Some(bytes) = self.materialize(matrix) {
-            return bytes == [b'M', b'A', b'S'] || bytes == [b'S', b'A', b'M'];
+    fn find_all(&self, matrix: &Matrix<'_>) -> Vec<Match> {
+        let mut out = vec![];
+        for row in 0..matrix.rows_count {
+            for col in 0..matrix.cols_count {
+                let at = Point {
+                    row: row as isize,
+                    col: col as isize,
+                };
+                for (variant, shape) in self.variants.iter().enumerate() {
+                    if !shape.matches_at(matrix, at) {
+                        continue;
+                    }
+                    let origin = self.origins[variant];
+                    let top_left = at.add(origin.row, origin.col);
+                    let extent = self.extents[variant];
+                    let bottom_right = top_left.add(extent.row, extent.col);
+                    // `.` cells match anything, but only inside the grid
+                    if top_left.row >= 0
+                        && top_left.col >= 0
+                        && bottom_right.row < matrix.rows_count as isize
+                        && bottom_right.col < matrix.cols_count as isize
+                    {
+                        out.push(Match {
+                            variant,
+                            at: top_left,
+                        });
+                    }
+                }
+            }
         }
-        false
+        out
     }
 }
 
@@ -88,29 +197,22 @@ struct Matrix<'a> {
     cols_count: usize,
 }
 
-fn solve(input: &[u8]) -> usize {
-    let mut out = 0;
-
-    let lines = input.split(|b| *b == b'\n').collect::<Vec<_>>();
-    let matrix = Matrix {
-        rows_count: lines.len(),
-        cols_count: lines[0].len(),
-        lines,
-    };
-    for row in 0..matrix.rows_count {
-        for col in 0..matrix.cols_count {
-            let start = Point {
-                row: row as isize,
-                col: col as isize,
-            };
-            let square = Square(start);
-            if square.matches(&matrix) {
-                out += 1;
-            }
+impl<'a> Matrix<'a> {
+    fn parse(input: &'a [u8]) -> Self {
+        let lines = input
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        Self {
+            rows_count: lines.len(),
+            cols_count: lines.first().map_or(0, |line| line.len()),
+            lines,
         }
     }
+}
 
-    out
+pub fn solve(input: &[u8]) -> usize {
+    Pattern::parse(X_MAS).find_all(&Matrix::parse(input)).len()
 }
 
 #[test]
@@ -119,3 +221,45 @@ fn test() {
     let output = solve(input);
     assert_eq!(output, 9);
 }
+
+#[test]
+fn test_pattern() {
+    assert_eq!(Pattern::parse(X_MAS).variants.len(), 4);
+    assert_eq!(Pattern::parse("A.A\n.A.\nA.A").variants.len(), 1);
+    assert_eq!(Pattern::parse("XM").variants.len(), 4);
+
+    let matrix = Matrix::parse(b"..X\n.M.\n...\n");
+    assert_eq!(
+        Pattern::parse("X.\n.M").find_all(&matrix),
+        [Match {
+            variant: 1,
+            at: Point { row: 0, col: 1 }
+        }]
+    );
+
+    let matrix = Matrix::parse(b"...\n.XM\n");
+    assert_eq!(
+        Pattern::parse("..\nXM").find_all(&matrix),
+        [Match {
+            variant: 0,
+            at: Point { row: 0, col: 1 }
+        }]
+    );
+    let matrix = Matrix::parse(b".M\n.X\n");
+    assert_eq!(
+        Pattern::parse("..\nXM").find_all(&matrix),
+        [Match {
+            variant: 3,
+            at: Point { row: 0, col: 0 }
+        }]
+    );
+    // the whole pattern must fit, not only its letters
+    assert_eq!(
+        Pattern::parse("..\nXM").find_all(&Matrix::parse(b"XM\n")),
+        []
+    );
+    assert_eq!(
+        Pattern::parse("X...\n......\n......").find_all(&Matrix::parse(b"X.\n")),
+        []
+    );
+}
@@ -0,0 +1,36 @@
+/// `before[i][j]` is set when rules, directly or through other pages of the update,
+/// require `pages[i]` to come before `pages[j]`.
+pub fn precedence(pages: &[usize], rule: impl Fn(usize, usize) -> bool) -> Vec<Vec<bool>> {
+    let mut before = pages
+        .iter()
+        .map(|a| pages.iter().map(|b| rule(*a, *b)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    // updates are short, so Floyd-Warshall is enough
+    for k in 0..pages.len() {
+        let after_k = before[k].clone();
+        for row in before.iter_mut().filter(|row| row[k]) {
+            for (cell, after) in row.iter_mut().zip(&after_k) {
+                *cell |= *after;
+            }
+        }
+    }
+    before
+}
+
+/// Checks every pair of pages against the rules between them and their transitive closure,
+/// comparing adjacent pages is enough only when the rules are total for the update.
+pub fn is_ordered(pages: &[usize], rule: impl Fn(usize, usize) -> bool) -> bool {
+    let before = precedence(pages, rule);
+    (0..pages.len()).all(|i| (i + 1..pages.len()).all(|j| !before[j][i]))
+}
+
+#[test]
+fn test_is_ordered() {
+    let rule = |a, b| matches!((a, b), (1, 2) | (2, 3));
+    // there's no rule between 1 and 4, 1 and 3 are ordered only through 2
+    assert!(is_ordered(&[1, 4, 2, 3], rule));
+    assert!(!is_ordered(&[3, 4, 1, 2], rule));
+    assert!(!is_ordered(&[3, 2], rule));
+    assert!(precedence(&[1, 4, 2, 3], rule)[0][3]);
+}
@@ -1,3 +1,8 @@
+#[path = "ordering.rs"]
+mod ordering;
+
+use std::collections::{HashMap, HashSet};
+
 fn main() {
     let input = include_str!("input.txt");
     let output = solve(input);
@@ -6,23 +11,25 @@ fn main() {
 
 #[derive(Debug)]
 struct OrderingMap {
-    map: [[bool; 100]; 100],
+    map: HashMap<usize, HashSet<usize>>,
 }
 
 impl OrderingMap {
     fn new(input: &str) -> Self {
-        let mut map = [[false; 100]; 100];
+        let mut map: HashMap<usize, HashSet<usize>> = HashMap::new();
         for line in input.lines() {
             let (before, after) = line.split_once('|').unwrap();
             let before = before.parse::<usize>().unwrap();
             let after = after.parse::<usize>().unwrap();
-            map[before][after] = true;
+            map.entry(before).or_default().insert(after);
         }
         Self { map }
     }
 
     fn is_valid(&self, before: usize, after: usize) -> bool {
-        self.map[before][after]
+        self.map
+            .get(&before)
+            .is_some_and(|after_set| after_set.contains(&after))
     }
 }
 
@@ -41,20 +48,18 @@ impl Pages {
         }
     }
 
+    /// Checks every pair of pages, rules may not be given for adjacent ones.
     fn is_valid(&self, map: &OrderingMap) -> bool {
-        self.pages
-            .iter()
-            .zip(self.pages.iter().skip(1))
-            .all(|(before, after)| map.is_valid(*before, *after))
+        ordering::is_ordered(&self.pages, |before, after| map.is_valid(before, after))
     }
 
     fn middle(&self) -> usize {
-        assert!(self.pages.len() % 2 != 0);
+        assert!(!self.pages.len().is_multiple_of(2));
         self.pages[self.pages.len() / 2]
     }
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let (ordering, pages) = input.trim().split_once("\n\n").unwrap();
 
     let ordering = OrderingMap::new(ordering);
@@ -75,4 +80,6 @@ fn test() {
     let input = include_str!("input_test.txt");
     let output = solve(input);
     assert_eq!(output, 143);
+
+    assert_eq!(solve("1|2\n2|3\n\n1,4,2,3,5\n3,4,1,2,5"), 2);
 }
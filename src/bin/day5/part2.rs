@@ -1,70 +1,114 @@
-use std::collections::HashSet;
+#[path = "ordering.rs"]
+mod ordering;
+
+use adventofcode2024::inspect::Report;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
 
 fn main() {
     let input = include_str!("input.txt");
-    let output = solve(input);
+    let output = match std::env::args().nth(1).as_deref() {
+        Some("--repair") => {
+            repair(input);
+            return;
+        }
+        _ => solve(input),
+    };
     println!("{}", output);
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Default)]
 struct Graph {
-    edges: [[bool; 100]; 100],
+    outgoing: HashMap<usize, HashSet<usize>>,
+    incoming: HashMap<usize, HashSet<usize>>,
     nodes: HashSet<usize>,
 }
 
+/// Pages forming a loop of rules, the first page is implicitly repeated at the end.
+#[derive(Debug, PartialEq, Eq)]
+struct Cycle(Vec<usize>);
+
+impl std::fmt::Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for node in &self.0 {
+            write!(f, "{node} -> ")?;
+        }
+        write!(f, "{}", self.0[0])
+    }
+}
+
 impl Graph {
     fn new(input: &str) -> Self {
-        let mut edges = [[false; 100]; 100];
-        let mut nodes = HashSet::new();
+        let mut graph = Self::default();
         for line in input.lines() {
             let (before, after) = line.split_once('|').unwrap();
             let before = before.parse::<usize>().unwrap();
             let after = after.parse::<usize>().unwrap();
-            edges[before][after] = true;
-            nodes.insert(before);
-            nodes.insert(after);
+            graph.add_edge(before, after);
         }
-        Self { edges, nodes }
+        graph
     }
 
-    fn connected(&self, before: usize, after: usize) -> bool {
-        self.edges[before][after]
+    fn add_edge(&mut self, before: usize, after: usize) {
+        self.outgoing.entry(before).or_default().insert(after);
+        self.incoming.entry(after).or_default().insert(before);
+        self.nodes.insert(before);
+        self.nodes.insert(after);
     }
 
-    fn subgraph(&self, nodes_to_take: &[usize]) -> Self {
-        let mut edges = [[false; 100]; 100];
-        let nodes = HashSet::from_iter(nodes_to_take.iter().copied());
-        for node1 in nodes_to_take {
-            for node2 in nodes_to_take {
-                edges[*node1][*node2] = self.edges[*node1][*node2];
-                edges[*node2][*node1] = self.edges[*node2][*node1];
-            }
+    fn remove_edge(&mut self, before: usize, after: usize) {
+        if let Some(outgoing) = self.outgoing.get_mut(&before) {
+            outgoing.remove(&after);
+        }
+        if let Some(incoming) = self.incoming.get_mut(&after) {
+            incoming.remove(&before);
         }
-        Self { edges, nodes }
     }
 
-    fn incoming_edges(&self, node: usize) -> Vec<usize> {
-        let mut out = vec![];
-        for other in self.nodes.iter() {
-            if self.edges[*other][node] {
-                out.push(*other);
+    fn connected(&self, before: usize, after: usize) -> bool {
+        self.outgoing
+            .get(&before)
+            .is_some_and(|outgoing| outgoing.contains(&after))
+    }
+
+    /// Returns a pair of nodes with no rule between them in either direction.
+    fn missing_rule(&self, nodes: &[usize]) -> Option<(usize, usize)> {
+        nodes.iter().enumerate().find_map(|(idx, before)| {
+            nodes[idx + 1..]
+                .iter()
+                .find(|after| {
+                    !self.connected(*before, **after) && !self.connected(**after, *before)
+                })
+                .map(|after| (*before, *after))
+        })
+    }
+
+    fn subgraph(&self, nodes_to_take: &[usize]) -> Self {
+        let mut graph = Self::default();
+        for node in nodes_to_take {
+            graph.nodes.insert(*node);
+            for other in self.outcoming_edges(*node) {
+                if nodes_to_take.contains(&other) {
+                    graph.add_edge(*node, other);
+                }
             }
         }
-        out
+        graph
     }
 
     fn outcoming_edges(&self, node: usize) -> Vec<usize> {
-        let mut out = vec![];
-        for other in self.nodes.iter() {
-            if self.edges[node][*other] {
-                out.push(*other);
-            }
-        }
-        out
+        self.outgoing
+            .get(&node)
+            .map(|outgoing| outgoing.iter().copied().collect())
+            .unwrap_or_default()
     }
 
     fn has_incoming_edges(&self, node: usize) -> bool {
-        !self.incoming_edges(node).is_empty()
+        self.incoming
+            .get(&node)
+            .is_some_and(|incoming| !incoming.is_empty())
     }
 
     fn nodes_with_no_incoming_edges(&self) -> Vec<usize> {
@@ -75,42 +119,53 @@ impl Graph {
             .collect()
     }
 
-    fn tsort(mut self) -> Vec<usize> {
+    fn tsort(mut self) -> Result<Vec<usize>, Cycle> {
         let mut l = vec![];
-        let mut s = HashSet::from_iter(self.nodes_with_no_incoming_edges());
+        // smallest page first, so that the order is the same when several are valid
+        let mut s = self
+            .nodes_with_no_incoming_edges()
+            .into_iter()
+            .map(Reverse)
+            .collect::<BinaryHeap<_>>();
 
-        while let Some(n) = s.sample() {
+        while let Some(Reverse(n)) = s.pop() {
             l.push(n);
 
             for m in self.outcoming_edges(n) {
-                self.edges[n][m] = false;
-                if self.incoming_edges(m).is_empty() {
-                    s.insert(m);
+                self.remove_edge(n, m);
+                if !self.has_incoming_edges(m) {
+                    s.push(Reverse(m));
                 }
             }
         }
 
-        for from in 0..100 {
-            for to in 0..100 {
-                if self.edges[from][to] {
-                    panic!("cycle detected, there's still an edge {from} -> {to}");
-                }
-            }
+        match self
+            .nodes
+            .iter()
+            .find(|node| self.has_incoming_edges(**node))
+        {
+            Some(node) => Err(self.find_cycle(*node)),
+            None => Ok(l),
         }
-
-        l
     }
-}
 
-trait HashSetExt<T: Copy + Eq + std::hash::Hash> {
-    fn sample(&mut self) -> Option<T>;
-}
-
-impl<T: Copy + Eq + std::hash::Hash> HashSetExt<T> for HashSet<T> {
-    fn sample(&mut self) -> Option<T> {
-        let elem = *self.iter().next()?;
-        self.remove(&elem);
-        Some(elem)
+    /// Walks incoming edges back from a node that tsort couldn't reach.
+    /// Every such node has an incoming edge from another one, so the walk loops.
+    fn find_cycle(&self, start: usize) -> Cycle {
+        let mut path = vec![start];
+        let mut node = start;
+        loop {
+            node = *self.incoming[&node].iter().min().unwrap();
+            if let Some(idx) = path.iter().position(|n| *n == node) {
+                let mut cycle = path.split_off(idx);
+                cycle.reverse();
+                // start from the smallest page so that the same cycle is always printed the same
+                let min = cycle.iter().enumerate().min_by_key(|(_, n)| **n).unwrap().0;
+                cycle.rotate_left(min);
+                return Cycle(cycle);
+            }
+            path.push(node);
+        }
     }
 }
 
@@ -129,26 +184,215 @@ impl Pages {
         }
     }
 
+    /// Checks every pair of pages against the rules between them and their transitive closure,
+    /// same as part 1, rules may not be given for adjacent pages.
     fn is_valid(&self, graph: &Graph) -> bool {
-        self.pages
-            .iter()
-            .zip(self.pages.iter().skip(1))
-            .all(|(before, after)| graph.connected(*before, *after))
+        ordering::is_ordered(&self.pages, |a, b| graph.connected(a, b))
     }
 
     fn fix(&mut self, graph: &Graph) {
         let subgraph = graph.subgraph(&self.pages);
-        let sorted = subgraph.tsort();
-        self.pages = sorted;
+        self.pages = subgraph
+            .tsort()
+            .unwrap_or_else(|cycle| panic!("cycle detected: {cycle}"));
+    }
+
+    /// Fixes the update by moving as few pages as possible, returns moved pages.
+    /// Pages that stay have no pair ordered against the rules or their transitive closure,
+    /// the rest is placed around them in rule order.
+    fn repair(&mut self, graph: &Graph) -> Vec<usize> {
+        let pages = std::mem::take(&mut self.pages);
+        let before = ordering::precedence(&pages, |a, b| graph.connected(a, b));
+        // pages i < j are an inversion when pages[j] must come before pages[i]
+        let kept = largest_antichain(pages.len(), |i, j| i < j && before[j][i]);
+
+        let mut subgraph = graph.subgraph(&pages);
+        let kept_pages = (0..pages.len())
+            .filter(|idx| kept.contains(idx))
+            .map(|idx| pages[idx])
+            .collect::<Vec<_>>();
+        for pair in kept_pages.windows(2) {
+            subgraph.add_edge(pair[0], pair[1]);
+        }
+        self.pages = subgraph
+            .tsort()
+            .unwrap_or_else(|cycle| panic!("cycle detected: {cycle}"));
+
+        pages
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !kept.contains(idx))
+            .map(|(_, page)| *page)
+            .collect()
     }
 
     fn middle(&self) -> usize {
-        assert!(self.pages.len() % 2 != 0);
+        assert!(!self.pages.len().is_multiple_of(2));
         self.pages[self.pages.len() / 2]
     }
 }
 
-fn solve(input: &str) -> usize {
+/// Returns a largest set of elements `0..n` with no two of them ordered by `less`,
+/// which must be a strict partial order.
+/// By Dilworth's theorem its size is `n` minus a maximum matching between
+/// lower and upper copies of elements, König's theorem recovers the set from the matching.
+fn largest_antichain(n: usize, less: impl Fn(usize, usize) -> bool) -> HashSet<usize> {
+    fn augment(
+        lower: usize,
+        n: usize,
+        less: &impl Fn(usize, usize) -> bool,
+        upper_match: &mut [Option<usize>],
+        seen: &mut [bool],
+    ) -> bool {
+        for upper in 0..n {
+            if less(lower, upper) && !seen[upper] {
+                seen[upper] = true;
+                let free = match upper_match[upper] {
+                    None => true,
+                    Some(other) => augment(other, n, less, upper_match, seen),
+                };
+                if free {
+                    upper_match[upper] = Some(lower);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    let mut upper_match = vec![None; n];
+    let lower_matched = (0..n)
+        .map(|lower| augment(lower, n, &less, &mut upper_match, &mut vec![false; n]))
+        .collect::<Vec<_>>();
+
+    // alternating paths from unmatched lower elements
+    let mut lower_seen = vec![false; n];
+    let mut upper_seen = vec![false; n];
+    let mut stack = (0..n).filter(|l| !lower_matched[*l]).collect::<Vec<_>>();
+    for lower in &stack {
+        lower_seen[*lower] = true;
+    }
+    while let Some(lower) = stack.pop() {
+        for upper in 0..n {
+            if less(lower, upper) && !upper_seen[upper] {
+                upper_seen[upper] = true;
+                if let Some(next) = upper_match[upper] {
+                    if !lower_seen[next] {
+                        lower_seen[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+    }
+
+    (0..n)
+        .filter(|x| lower_seen[*x] && !upper_seen[*x])
+        .collect()
+}
+
+fn repair(input: &str) {
+    let (ordering, pages) = input.trim().split_once("\n\n").unwrap();
+    let graph = Graph::new(ordering);
+
+    let mut total = 0;
+    for line in pages.lines() {
+        let mut pages = Pages::new(line);
+        if pages.is_valid(&graph) {
+            continue;
+        }
+        let moved = pages.repair(&graph);
+        total += moved.len();
+        println!(
+            "{line} -> {}: moved {}",
+            pages
+                .pages
+                .iter()
+                .map(|page| page.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            moved
+                .iter()
+                .map(|page| page.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+    }
+    println!("total moves: {total}");
+}
+
+pub fn inspect(input: &str) -> Report {
+    let mut report = Report::new();
+    let (ordering, pages) = input.trim().split_once("\n\n").unwrap();
+
+    let rules = ordering
+        .lines()
+        .map(|line| {
+            let (before, after) = line.split_once('|').unwrap();
+            (
+                before.parse::<usize>().unwrap(),
+                after.parse::<usize>().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let updates = pages
+        .lines()
+        .map(|line| {
+            line.split(',')
+                .map(|s| s.parse::<usize>().unwrap())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let max_page = rules
+        .iter()
+        .flat_map(|(before, after)| [*before, *after])
+        .chain(updates.iter().flatten().copied())
+        .max()
+        .unwrap_or_default();
+
+    report.fact("rules", rules.len());
+    report.fact("updates", updates.len());
+    report.fact("max page number", max_page);
+
+    report.check(
+        "every update has a middle page",
+        updates
+            .iter()
+            .position(|update| update.len().is_multiple_of(2))
+            .map(|idx| format!("update {} has {} pages", idx + 1, updates[idx].len())),
+    );
+    let graph = Graph::new(ordering);
+    report.fact("whole rule graph is acyclic", graph.clone().tsort().is_ok());
+    report.check(
+        "rules restricted to every update are acyclic",
+        updates.iter().enumerate().find_map(|(idx, update)| {
+            graph
+                .subgraph(update)
+                .tsort()
+                .err()
+                .map(|cycle| format!("update {} has a cycle {cycle}", idx + 1))
+        }),
+    );
+    report.fact(
+        "updates with total rules",
+        updates
+            .iter()
+            .filter(|update| graph.missing_rule(update).is_none())
+            .count(),
+    );
+    report.check(
+        "rules are total for every update, so adjacent checks are enough",
+        updates.iter().enumerate().find_map(|(idx, update)| {
+            graph
+                .missing_rule(update)
+                .map(|(a, b)| format!("update {} has no rule between {a} and {b}", idx + 1))
+        }),
+    );
+
+    report
+}
+
+pub fn solve(input: &str) -> usize {
     let (ordering, pages) = input.trim().split_once("\n\n").unwrap();
 
     let graph = Graph::new(ordering);
@@ -170,4 +414,71 @@ fn test() {
     let input = include_str!("input_test.txt");
     let output = solve(input);
     assert_eq!(output, 123);
+
+    // the first update is valid, there's no rule between 1 and 4
+    assert_eq!(solve("1|2\n2|3\n\n1,4,2,3,5\n3,1,2"), 2);
+}
+
+#[test]
+fn test_cycle() {
+    let graph = Graph::new("1|200\n200|3\n3|1\n3|4\n4|5");
+    assert_eq!(graph.clone().tsort(), Err(Cycle(vec![1, 200, 3])));
+    assert_eq!(Cycle(vec![1, 200, 3]).to_string(), "1 -> 200 -> 3 -> 1");
+    assert_eq!(
+        graph.subgraph(&[200, 3, 4, 5]).tsort(),
+        Ok(vec![200, 3, 4, 5])
+    );
+}
+
+#[test]
+fn test_repair() {
+    let input = include_str!("input_test.txt");
+    let (ordering, _) = input.split_once("\n\n").unwrap();
+    let graph = Graph::new(ordering);
+
+    let mut pages = Pages::new("97,13,75,29,47");
+    assert_eq!(pages.repair(&graph), [13, 29]);
+    assert_eq!(pages.pages, [97, 75, 47, 29, 13]);
+
+    let mut pages = Pages::new("61,13,29");
+    assert_eq!(pages.repair(&graph), [13]);
+    assert_eq!(pages.pages, [61, 29, 13]);
+
+    // a single sorted order would keep only one of 3 and 2 in place
+    let graph = Graph::new("1|2");
+    let mut pages = Pages::new("3,2,1");
+    assert_eq!(pages.repair(&graph), [2]);
+    assert_eq!(pages.pages, [3, 1, 2]);
+    assert!(pages.is_valid(&graph));
+
+    // the fewest pages to move is the update length minus the largest set of pages
+    // that can stay in place, i.e. that have no rule against their current order
+    let moves = |rules: &str, update: &str| {
+        let graph = Graph::new(rules);
+        let mut pages = Pages::new(update);
+        let moved = pages.repair(&graph);
+        assert!(pages.is_valid(&graph), "{rules:?} {update:?}");
+        moved.len()
+    };
+    assert_eq!(moves("1|2\n2|3", "1,2,3"), 0);
+    assert_eq!(moves("1|2\n2|3", "3,2,1"), 2);
+    // 1 alone goes first, 2 and 3 stay
+    assert_eq!(moves("1|2\n1|3", "2,3,1"), 1);
+    // two independent chains, one page of each crossing pair has to move
+    assert_eq!(moves("1|3\n2|4", "3,4,1,2"), 2);
+    // 5 only has to jump over pages it precedes, 3|4 is already in order
+    assert_eq!(moves("5|1\n5|2\n3|4", "1,2,3,4,5"), 1);
+    // 3 and 1 are reversed only through 2, still only one of the three can stay
+    assert_eq!(moves("1|2\n2|3", "3,4,2,1"), 2);
+    // a long chain only needs its misplaced end moved
+    assert_eq!(moves("1|2\n2|3\n3|4\n4|5\n5|6", "6,1,2,3,4,5"), 1);
+}
+
+#[test]
+fn test_strict() {
+    let graph = Graph::new("1|2\n2|3");
+    // there's no rule between 1 and 4, 1 and 3 are ordered only through 2
+    assert!(Pages::new("1,4,2,3").is_valid(&graph));
+    assert!(!Pages::new("3,4,1,2").is_valid(&graph));
+    assert_eq!(graph.missing_rule(&[1, 2, 3]), Some((1, 3)));
 }
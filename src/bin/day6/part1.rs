@@ -1,213 +1,63 @@
+#[path = "patrol.rs"]
+mod patrol;
+
+use patrol::{Cell, Frame, JumpTable, Location, Matrix, Turn};
 use std::collections::HashSet;
 
 fn main() {
     let input = include_bytes!("input.txt");
-    let output = solve(input);
-    println!("{}", output);
-}
-
-#[derive(Debug, Clone, Copy)]
-enum Cell {
-    Blocked,
-    Empty,
-    Visited,
-}
-
-impl From<u8> for Cell {
-    fn from(byte: u8) -> Self {
-        match byte {
-            b'.' => Self::Empty,
-            b'#' => Self::Blocked,
-            b'^' => Self::Visited,
-            _ => unimplemented!("wrong cell byte: {}", byte as char),
-        }
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let turn = Turn::take_arg(&mut args);
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    match args.as_slice() {
+        ["--render"] => print!("{}", render(input, turn)),
+        ["--render", "--ppm", path] => {
+            let file = std::fs::File::create(path).unwrap();
+            render(input, turn)
+                .write_ppm(std::io::BufWriter::new(file), 4)
+                .unwrap()
+        }
+        _ => println!("{}", solve_with(input, turn)),
     }
 }
 
-#[derive(Debug)]
-struct Matrix {
-    rows: Vec<Vec<Cell>>,
-    rows_count: usize,
-    cols_count: usize,
-}
-
-impl Matrix {
-    fn parse(bytes: &[u8]) -> (Self, Location) {
-        let mut rows = vec![];
-        let mut startrow = None;
-        let mut startcol = None;
-
-        for (rowno, row_bytes) in bytes.split(|b| *b == b'\n').enumerate() {
-            if row_bytes.is_empty() {
-                continue;
-            }
-            let mut row = vec![];
-            for (colno, byte) in row_bytes.iter().enumerate() {
-                row.push(Cell::from(*byte));
-                if *byte == b'^' {
-                    startrow = Some(rowno);
-                    startcol = Some(colno);
-                }
-            }
-            rows.push(row);
-        }
-
-        (
-            Self {
-                rows_count: rows.len(),
-                cols_count: rows.first().unwrap().len(),
-                rows,
-            },
-            Location {
-                dir: Direction::Up,
-                row: startrow.unwrap() as isize,
-                col: startcol.unwrap() as isize,
-            },
-        )
-    }
-
-    fn visit(&mut self, row: usize, col: usize) {
-        match self.rows[row][col] {
-            Cell::Blocked => panic!("bug"),
-            Cell::Empty | Cell::Visited => self.rows[row][col] = Cell::Visited,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-    Left,
-    Up,
-    Right,
-    Down,
+fn render(input: &[u8], turn: Turn) -> Frame {
+    let (matrix, guards) = Matrix::parse_guards(input);
+    let (stops, _) = JumpTable::new(&matrix).patrol(&matrix, &guards, None, turn);
+    let paths = stops
+        .iter()
+        .map(|stops| patrol::path(stops))
+        .collect::<Vec<_>>();
+    Frame::new(&matrix, &paths, &[])
 }
 
-impl Direction {
-    fn turn_right(self) -> Self {
-        match self {
-            Self::Left => Self::Up,
-            Self::Up => Self::Right,
-            Self::Right => Self::Down,
-            Self::Down => Self::Left,
-        }
-    }
-
-    fn drow_dcol(self) -> (isize, isize) {
-        match self {
-            Self::Left => (0, -1),
-            Self::Up => (-1, 0),
-            Self::Right => (0, 1),
-            Self::Down => (1, 0),
-        }
+/// Cells walked through by any guard, `None` if guards loop.
+fn simulate(matrix: &Matrix, guards: &[Location], turn: Turn) -> Option<HashSet<(usize, usize)>> {
+    let (stops, looped) = JumpTable::new(matrix).patrol(matrix, guards, None, turn);
+    if looped {
+        return None;
     }
+    Some(
+        stops
+            .iter()
+            .flat_map(|stops| patrol::path(stops))
+            .map(|location| (location.row, location.col))
+            .collect(),
+    )
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Location {
-    dir: Direction,
-    row: isize,
-    col: isize,
+pub fn solve(input: &[u8]) -> usize {
+    solve_with(input, Turn::Right)
 }
 
-impl Location {
-    fn next(self, matrix: &Matrix) -> Option<(usize, usize, Direction)> {
-        if let Some(answer) = self.next_in_the_same_direction().validate(matrix) {
-            return Some(answer);
-        }
-
-        // try 4 rotations
-        for i in 0..4 {
-            if let Some(answer) = self.next_turning_right(i).validate(matrix) {
-                return Some(answer);
-            }
-        }
+fn solve_with(input: &[u8], turn: Turn) -> usize {
+    let (mut matrix, guards) = Matrix::parse_guards(input);
 
-        None
-    }
-
-    fn next_in_the_same_direction(self) -> Self {
-        let (drow, dcol) = self.dir.drow_dcol();
-        Self {
-            dir: self.dir,
-            row: self.row + drow,
-            col: self.col + dcol,
-        }
-    }
-
-    fn next_turning_right(self, n: u8) -> Self {
-        let mut dir = self.dir;
-        for _ in 0..n {
-            dir = dir.turn_right();
-        }
-        let (drow, dcol) = dir.drow_dcol();
-        Self {
-            dir,
-            row: self.row + drow,
-            col: self.col + dcol,
-        }
-    }
-
-    fn validate(self, matrix: &Matrix) -> Option<(usize, usize, Direction)> {
-        let Self { row, col, dir } = self;
-        if row < 0 || col < 0 {
-            return None;
-        }
-        let row = row as usize;
-        let col = col as usize;
-        if row >= matrix.rows_count || col >= matrix.cols_count {
-            return None;
-        }
+    let visited = simulate(&matrix, &guards, turn).expect("found a loop");
+    for (row, col) in visited {
         match matrix.rows[row][col] {
-            Cell::Blocked => None,
-            Cell::Empty | Cell::Visited => Some((row, col, dir)),
-        }
-    }
-
-    fn is_dead_end(self, matrix: &Matrix) -> bool {
-        match self {
-            Self {
-                dir: Direction::Left,
-                col: 0,
-                ..
-            } => true,
-            Self {
-                dir: Direction::Up,
-                row: 0,
-                ..
-            } => true,
-            Self {
-                dir: Direction::Right,
-                col,
-                ..
-            } if col == (matrix.cols_count - 1) as isize => true,
-            Self {
-                dir: Direction::Down,
-                row,
-                ..
-            } if row == (matrix.rows_count - 1) as isize => true,
-            _ => false,
-        }
-    }
-}
-
-fn solve(input: &[u8]) -> usize {
-    let (mut matrix, mut location) = Matrix::parse(input);
-
-    let mut visited = HashSet::new();
-    visited.insert(location);
-
-    while let Some((row, col, dir)) = location.next(&matrix) {
-        matrix.visit(row, col);
-        location = Location {
-            dir,
-            row: row as isize,
-            col: col as isize,
-        };
-        if location.is_dead_end(&matrix) {
-            break;
-        }
-        if !visited.insert(location) {
-            panic!("found a loop");
+            Cell::Blocked => panic!("bug"),
+            Cell::Empty | Cell::Visited => matrix.rows[row][col] = Cell::Visited,
         }
     }
 
@@ -228,3 +78,26 @@ fn test() {
     let output = solve(input);
     assert_eq!(output, 41);
 }
+
+#[test]
+fn test_guards() {
+    let input = include_bytes!("input_test.txt");
+    let (matrix, guards) = Matrix::parse_guards(input);
+    assert_eq!(simulate(&matrix, &guards, Turn::Right).unwrap().len(), 41);
+
+    // going up, the guard hits the obstacle and either goes back down or leaves to the left
+    let input = b"..#..\n.....\n..^..\n.....\n";
+    assert_eq!(solve_with(input, Turn::Around), 3);
+    assert_eq!(solve_with(input, Turn::Left), 4);
+
+    // both guards want (1, 2), so they turn and leave
+    let input = b".....\n.>.<.\n";
+    let (matrix, guards) = Matrix::parse_guards(input);
+    let visited = simulate(&matrix, &guards, Turn::Right).unwrap();
+    assert_eq!(visited.len(), 3);
+    assert!(!visited.contains(&(1, 2)));
+
+    // facing each other with nowhere to go
+    let (matrix, guards) = Matrix::parse_guards(b"#><#\n####\n");
+    assert_eq!(simulate(&matrix, &guards, Turn::Around), None);
+}
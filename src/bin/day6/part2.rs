@@ -1,249 +1,190 @@
+#[path = "patrol.rs"]
+mod patrol;
+
+use patrol::{Frame, JumpTable, Location, Matrix, Turn};
 use rayon::prelude::*;
-use std::collections::HashSet;
 
 fn main() {
     let input = include_bytes!("input.txt");
-    let output = solve(input);
-    println!("{}", output);
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let turn = Turn::take_arg(&mut args);
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    match args.as_slice() {
+        ["--render"] => print!("{}", render(input, turn)),
+        ["--render", "--ppm", path] => {
+            let file = std::fs::File::create(path).unwrap();
+            render(input, turn)
+                .write_ppm(std::io::BufWriter::new(file), 4)
+                .unwrap()
+        }
+        ["--stats"] => {
+            let (candidates, loops) = count_loops(input, turn);
+            println!("{candidates} candidates, {loops} loops");
+        }
+        _ => println!("{}", count_loops(input, turn).1),
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
-enum Cell {
-    Blocked,
-    Empty,
-    Visited,
-}
+/// Only cells on the original routes can change them, returns guards to start
+/// checking an obstacle on each cell from, and the cell.
+/// A single guard is checked from the moment it's about to step on the cell,
+/// several guards get in each other's way, so they start over.
+fn candidates(
+    matrix: &Matrix,
+    guards: &[Location],
+    paths: &[Vec<Location>],
+) -> Vec<(Vec<Location>, (usize, usize))> {
+    let mut seen = vec![false; matrix.rows_count * matrix.cols_count];
+    for guard in guards {
+        seen[guard.row * matrix.cols_count + guard.col] = true;
+    }
 
-impl From<u8> for Cell {
-    fn from(byte: u8) -> Self {
-        match byte {
-            b'.' => Self::Empty,
-            b'#' => Self::Blocked,
-            b'^' => Self::Visited,
-            _ => unimplemented!("wrong cell byte: {}", byte as char),
+    let mut out = vec![];
+    for path in paths {
+        for step in path.windows(2) {
+            let (before, cell) = (step[0], step[1]);
+            let seen = &mut seen[cell.row * matrix.cols_count + cell.col];
+            if *seen {
+                // the guard already went through it, an obstacle would've stopped it earlier
+                continue;
+            }
+            *seen = true;
+            let starts = match guards {
+                [_] => vec![Location {
+                    dir: cell.dir,
+                    ..before
+                }],
+                _ => guards.to_vec(),
+            };
+            out.push((starts, (cell.row, cell.col)));
         }
     }
+    out
 }
 
-#[derive(Debug, Clone)]
-struct Matrix {
-    rows: Vec<Vec<Cell>>,
-    rows_count: usize,
-    cols_count: usize,
+/// Original routes of all guards and obstacles that make them loop.
+struct Loops {
+    matrix: Matrix,
+    paths: Vec<Vec<Location>>,
+    obstacles: Vec<(usize, usize)>,
+    /// Number of checked obstacle positions.
+    checked: usize,
 }
 
-impl Matrix {
-    fn parse(bytes: &[u8]) -> (Self, Location) {
-        let mut rows = vec![];
-        let mut startrow = None;
-        let mut startcol = None;
+fn find_loops(input: &[u8], turn: Turn) -> Loops {
+    let (matrix, guards) = Matrix::parse_guards(input);
+    let table = JumpTable::new(&matrix);
 
-        for (rowno, row_bytes) in bytes.split(|b| *b == b'\n').enumerate() {
-            if row_bytes.is_empty() {
-                continue;
-            }
-            let mut row = vec![];
-            for (colno, byte) in row_bytes.iter().enumerate() {
-                row.push(Cell::from(*byte));
-                if *byte == b'^' {
-                    startrow = Some(rowno);
-                    startcol = Some(colno);
-                }
-            }
-            rows.push(row);
-        }
+    let (stops, _) = table.patrol(&matrix, &guards, None, turn);
+    let paths = stops
+        .iter()
+        .map(|stops| patrol::path(stops))
+        .collect::<Vec<_>>();
+    let candidates = candidates(&matrix, &guards, &paths);
 
-        (
-            Self {
-                rows_count: rows.len(),
-                cols_count: rows.first().unwrap().len(),
-                rows,
-            },
-            Location {
-                dir: Direction::Up,
-                row: startrow.unwrap() as isize,
-                col: startcol.unwrap() as isize,
-            },
-        )
-    }
+    let obstacles = candidates
+        .par_iter()
+        .filter(|(starts, obstacle)| table.patrol(&matrix, starts, Some(*obstacle), turn).1)
+        .map(|(_, obstacle)| *obstacle)
+        .collect::<Vec<_>>();
 
-    fn visit(&mut self, row: usize, col: usize) {
-        match self.rows[row][col] {
-            Cell::Blocked => panic!("bug"),
-            Cell::Empty | Cell::Visited => self.rows[row][col] = Cell::Visited,
-        }
+    Loops {
+        checked: candidates.len(),
+        matrix,
+        paths,
+        obstacles,
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-    Left,
-    Up,
-    Right,
-    Down,
+/// Draws the original routes with every obstacle that makes guards loop.
+fn render(input: &[u8], turn: Turn) -> Frame {
+    let loops = find_loops(input, turn);
+    Frame::new(&loops.matrix, &loops.paths, &loops.obstacles)
 }
 
-impl Direction {
-    fn turn_right(self) -> Self {
-        match self {
-            Self::Left => Self::Up,
-            Self::Up => Self::Right,
-            Self::Right => Self::Down,
-            Self::Down => Self::Left,
-        }
-    }
-
-    fn drow_dcol(self) -> (isize, isize) {
-        match self {
-            Self::Left => (0, -1),
-            Self::Up => (-1, 0),
-            Self::Right => (0, 1),
-            Self::Down => (1, 0),
-        }
-    }
+/// Returns the number of checked obstacle positions and how many of them make guards loop.
+fn count_loops(input: &[u8], turn: Turn) -> (usize, usize) {
+    let loops = find_loops(input, turn);
+    (loops.checked, loops.obstacles.len())
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Location {
-    dir: Direction,
-    row: isize,
-    col: isize,
+pub fn solve(input: &[u8]) -> usize {
+    count_loops(input, Turn::Right).1
 }
 
-impl Location {
-    fn next(self, matrix: &Matrix) -> Option<(usize, usize, Direction)> {
-        if let Some(answer) = self.next_in_the_same_direction().validate(matrix) {
-            return Some(answer);
-        }
-
-        // try 4 rotations
-        for i in 0..4 {
-            if let Some(answer) = self.next_turning_right(i).validate(matrix) {
-                return Some(answer);
-            }
-        }
-
-        None
-    }
-
-    fn next_in_the_same_direction(self) -> Self {
-        let (drow, dcol) = self.dir.drow_dcol();
-        Self {
-            dir: self.dir,
-            row: self.row + drow,
-            col: self.col + dcol,
-        }
-    }
-
-    fn next_turning_right(self, n: u8) -> Self {
-        let mut dir = self.dir;
-        for _ in 0..n {
-            dir = dir.turn_right();
-        }
-        let (drow, dcol) = dir.drow_dcol();
-        Self {
-            dir,
-            row: self.row + drow,
-            col: self.col + dcol,
-        }
-    }
-
-    fn validate(self, matrix: &Matrix) -> Option<(usize, usize, Direction)> {
-        let Self { row, col, dir } = self;
-        if row < 0 || col < 0 {
-            return None;
-        }
-        let row = row as usize;
-        let col = col as usize;
-        if row >= matrix.rows_count || col >= matrix.cols_count {
-            return None;
-        }
-        match matrix.rows[row][col] {
-            Cell::Blocked => None,
-            Cell::Empty | Cell::Visited => Some((row, col, dir)),
-        }
-    }
-
-    fn is_dead_end(self, matrix: &Matrix) -> bool {
-        match self {
-            Self {
-                dir: Direction::Left,
-                col: 0,
-                ..
-            } => true,
-            Self {
-                dir: Direction::Up,
-                row: 0,
-                ..
-            } => true,
-            Self {
-                dir: Direction::Right,
-                col,
-                ..
-            } if col == (matrix.cols_count - 1) as isize => true,
-            Self {
-                dir: Direction::Down,
-                row,
-                ..
-            } if row == (matrix.rows_count - 1) as isize => true,
-            _ => false,
-        }
-    }
+#[test]
+fn test() {
+    let input = include_bytes!("input_test.txt");
+    let output = solve(input);
+    assert_eq!(output, 6);
+    assert_eq!(count_loops(input, Turn::Right), (40, 6));
 }
 
-fn is_loop(mut matrix: Matrix, mut location: Location, rowno: usize, colno: usize) -> bool {
-    if location.row as usize == rowno && location.col as usize == colno {
-        // can't have at the starting point
-        return false;
-    }
-    matrix.rows[rowno][colno] = Cell::Blocked;
-
-    let mut visited = HashSet::new();
-    visited.insert(location);
-
-    while let Some((row, col, dir)) = location.next(&matrix) {
-        matrix.visit(row, col);
-        location = Location {
-            dir,
-            row: row as isize,
-            col: col as isize,
-        };
-        if location.is_dead_end(&matrix) {
-            break;
-        }
-        if !visited.insert(location) {
-            // LOOP
-            return true;
-        }
-    }
-
-    false
+#[test]
+fn test_jump() {
+    let (matrix, guards) = Matrix::parse_guards(include_bytes!("input_test.txt"));
+    let start = guards[0];
+    let table = JumpTable::new(&matrix);
+
+    let stop = table.jump(start, None).unwrap();
+    assert_eq!((stop.row, stop.col), (1, 4));
+    let stop = table.jump(start, Some((3, 4))).unwrap();
+    assert_eq!((stop.row, stop.col), (4, 4));
+    assert_eq!(
+        table.jump(start, Some((1, 5))),
+        Some(Location { row: 1, ..start })
+    );
+    assert!(!table.patrol(&matrix, &[start], None, Turn::Right).1);
+    assert!(table.patrol(&matrix, &[start], Some((6, 3)), Turn::Right).1);
 }
 
-fn solve(input: &[u8]) -> usize {
-    let (matrix, location) = Matrix::parse(input);
+#[test]
+fn test_guards() {
+    // the example map with a second guard, every free cell is checked the slow way
+    let input = b"....#.....\n.........#\n..........\n..#.......\n.......#..\n..........\n.#..^.....\n........#.\n#.....<...\n......#...\n";
+    let (matrix, guards) = Matrix::parse_guards(input);
+    let table = JumpTable::new(&matrix);
 
-    let mut candidates = vec![];
+    let mut loops = 0;
     for row in 0..matrix.rows_count {
         for col in 0..matrix.cols_count {
-            candidates.push((row, col));
+            let free = matches!(matrix.rows[row][col], patrol::Cell::Empty);
+            if free
+                && table
+                    .patrol(&matrix, &guards, Some((row, col)), Turn::Right)
+                    .1
+            {
+                loops += 1;
+            }
         }
     }
-
-    let locs = candidates
-        .par_iter()
-        .filter(|(row, col)| {
-            let matrix = matrix.clone();
-            is_loop(matrix, location, *row, *col)
-        })
-        .collect::<Vec<_>>();
-
-    locs.len()
+    let (checked, found) = count_loops(input, Turn::Right);
+    assert!(loops > 0);
+    assert_eq!(found, loops);
+    assert!(checked < matrix.rows_count * matrix.cols_count);
 }
 
 #[test]
-fn test() {
+fn test_render() {
     let input = include_bytes!("input_test.txt");
-    let output = solve(input);
-    assert_eq!(output, 6);
+    assert_eq!(
+        render(input, Turn::Right).to_string(),
+        "\
+....#.....
+....+---+#
+....|...|.
+..#.|...|.
+..+-+-+#|.
+..|.|.|.|.
+.#+O^-+-+.
+.+----OO#.
+#O-O--+|..
+......#O..
+"
+    );
+
+    let mut ppm = vec![];
+    render(input, Turn::Right).write_ppm(&mut ppm, 2).unwrap();
+    assert!(ppm.starts_with(b"P6 20 20 255\n"));
+    assert_eq!(ppm.len(), "P6 20 20 255\n".len() + 20 * 20 * 3);
 }
@@ -0,0 +1,477 @@
+use adventofcode2024::args::take_arg;
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Cell {
+    Blocked,
+    Empty,
+    Visited,
+}
+
+impl From<u8> for Cell {
+    fn from(byte: u8) -> Self {
+        match byte {
+            b'.' => Self::Empty,
+            b'#' => Self::Blocked,
+            b'^' | b'>' | b'v' | b'<' => Self::Visited,
+            _ => panic!("wrong cell byte: {:?}", byte as char),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Matrix {
+    pub rows: Vec<Vec<Cell>>,
+    pub rows_count: usize,
+    pub cols_count: usize,
+}
+
+impl Matrix {
+    pub fn parse_guards(bytes: &[u8]) -> (Self, Vec<Location>) {
+        let mut rows = vec![];
+        let mut guards = vec![];
+
+        for (rowno, row_bytes) in bytes.split(|b| *b == b'\n').enumerate() {
+            if row_bytes.is_empty() {
+                continue;
+            }
+            let mut row = vec![];
+            for (colno, byte) in row_bytes.iter().enumerate() {
+                row.push(Cell::from(*byte));
+                if let Some(dir) = Direction::from_glyph(*byte) {
+                    guards.push(Location {
+                        dir,
+                        row: rowno,
+                        col: colno,
+                    });
+                }
+            }
+            rows.push(row);
+        }
+
+        (
+            Self {
+                rows_count: rows.len(),
+                cols_count: rows.first().unwrap().len(),
+                rows,
+            },
+            guards,
+        )
+    }
+
+    fn is_blocked(&self, row: isize, col: isize) -> Option<bool> {
+        if row < 0 || col < 0 {
+            return None;
+        }
+        let cell = self.rows.get(row as usize)?.get(col as usize)?;
+        Some(matches!(cell, Cell::Blocked))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Left,
+    Up,
+    Right,
+    Down,
+}
+
+impl Direction {
+    const ALL: [Self; 4] = [Self::Left, Self::Up, Self::Right, Self::Down];
+
+    pub fn from_glyph(byte: u8) -> Option<Self> {
+        match byte {
+            b'<' => Some(Self::Left),
+            b'^' => Some(Self::Up),
+            b'>' => Some(Self::Right),
+            b'v' => Some(Self::Down),
+            _ => None,
+        }
+    }
+
+    pub fn glyph(self) -> u8 {
+        match self {
+            Self::Left => b'<',
+            Self::Up => b'^',
+            Self::Right => b'>',
+            Self::Down => b'v',
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::Left => Self::Up,
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+        }
+    }
+
+    pub fn drow_dcol(self) -> (isize, isize) {
+        match self {
+            Self::Left => (0, -1),
+            Self::Up => (-1, 0),
+            Self::Right => (0, 1),
+            Self::Down => (1, 0),
+        }
+    }
+}
+
+/// What a guard does when facing an obstacle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    Right,
+    Left,
+    Around,
+}
+
+impl Turn {
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "right" => Self::Right,
+            "left" => Self::Left,
+            "around" => Self::Around,
+            _ => panic!("unknown turn {s:?}, expected right, left or around"),
+        }
+    }
+
+    /// Removes `--turn <turn>` from command line arguments, turning right by default.
+    pub fn take_arg(args: &mut Vec<String>) -> Self {
+        take_arg(args, "--turn").map_or(Self::Right, |turn| Self::parse(&turn))
+    }
+
+    pub fn apply(self, dir: Direction) -> Direction {
+        match self {
+            Self::Right => dir.turn_right(),
+            Self::Left => dir.turn_right().turn_right().turn_right(),
+            Self::Around => dir.turn_right().turn_right(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub dir: Direction,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// For every cell and direction, the cell where the guard stops in front of the next obstacle.
+/// `None` means that the guard walks off the map.
+#[derive(Debug)]
+pub struct JumpTable {
+    stops: [Vec<Option<(usize, usize)>>; 4],
+    rows_count: usize,
+    cols_count: usize,
+}
+
+impl JumpTable {
+    pub fn new(matrix: &Matrix) -> Self {
+        let (rows_count, cols_count) = (matrix.rows_count, matrix.cols_count);
+        let stops = Direction::ALL.map(|dir| {
+            let (drow, dcol) = dir.drow_dcol();
+            let mut stops = vec![None; rows_count * cols_count];
+            // cells closer to the obstacles are filled first
+            let rows = (0..rows_count).collect::<Vec<_>>();
+            let cols = (0..cols_count).collect::<Vec<_>>();
+            let rows = if drow > 0 {
+                rows.into_iter().rev().collect()
+            } else {
+                rows
+            };
+            let cols = if dcol > 0 {
+                cols.into_iter().rev().collect()
+            } else {
+                cols
+            };
+
+            for row in &rows {
+                for col in &cols {
+                    let (next_row, next_col) = (*row as isize + drow, *col as isize + dcol);
+                    stops[row * cols_count + col] = match matrix.is_blocked(next_row, next_col) {
+                        None => None,
+                        Some(true) => Some((*row, *col)),
+                        Some(false) => stops[next_row as usize * cols_count + next_col as usize],
+                    };
+                }
+            }
+            stops
+        });
+
+        Self {
+            stops,
+            rows_count,
+            cols_count,
+        }
+    }
+
+    /// Moves the guard straight until it faces an obstacle, `overlay` is an extra one.
+    pub fn jump(&self, from: Location, overlay: Option<(usize, usize)>) -> Option<Location> {
+        let Location { dir, row, col } = from;
+        let (drow, dcol) = dir.drow_dcol();
+        let distance = |(r, c): (usize, usize)| {
+            (r as isize - row as isize) * drow + (c as isize - col as isize) * dcol
+        };
+
+        let mut stop = self.stops[dir as usize][row * self.cols_count + col];
+        if let Some((orow, ocol)) = overlay {
+            let ahead = if drow == 0 { orow == row } else { ocol == col };
+            let odistance = distance((orow, ocol));
+            if ahead && odistance > 0 && stop.is_none_or(|stop| odistance <= distance(stop)) {
+                stop = Some((
+                    (orow as isize - drow) as usize,
+                    (ocol as isize - dcol) as usize,
+                ));
+            }
+        }
+
+        stop.map(|(row, col)| Location { dir, row, col })
+    }
+
+    /// The last cell before the guard walks off the map going straight.
+    fn exit(&self, from: Location) -> Location {
+        let Location { dir, row, col } = from;
+        match dir {
+            Direction::Left => Location { dir, row, col: 0 },
+            Direction::Up => Location { dir, row: 0, col },
+            Direction::Right => Location {
+                dir,
+                row,
+                col: self.cols_count - 1,
+            },
+            Direction::Down => Location {
+                dir,
+                row: self.rows_count - 1,
+                col,
+            },
+        }
+    }
+
+    /// How many ticks all guards can go straight without turning or getting in each other's way.
+    fn free_ticks(&self, guards: &[Option<Location>], overlay: Option<(usize, usize)>) -> usize {
+        let guards = guards.iter().flatten().collect::<Vec<_>>();
+        let mut ticks = usize::MAX;
+        for (idx, guard) in guards.iter().enumerate() {
+            let end = self
+                .jump(**guard, overlay)
+                .unwrap_or_else(|| self.exit(**guard));
+            ticks = ticks.min(guard.row.abs_diff(end.row) + guard.col.abs_diff(end.col));
+
+            // guards interfere only 2 cells apart or closer, and get closer by 2 cells per tick at most
+            for other in &guards[idx + 1..] {
+                let distance = guard.row.abs_diff(other.row) + guard.col.abs_diff(other.col);
+                ticks = ticks.min((distance - 1) / 2);
+            }
+        }
+        ticks
+    }
+
+    /// Moves every guard one step or turns it, guards that turn or leave the map get a stop.
+    fn tick(
+        matrix: &Matrix,
+        guards: &mut [Option<Location>],
+        stops: &mut [Vec<Location>],
+        overlay: Option<(usize, usize)>,
+        turn: Turn,
+    ) {
+        let taken = guards
+            .iter()
+            .flatten()
+            .map(|guard| (guard.row, guard.col))
+            .collect::<HashSet<_>>();
+        let ahead = guards
+            .iter()
+            .map(|guard| {
+                let guard = (*guard)?;
+                let (drow, dcol) = guard.dir.drow_dcol();
+                let (row, col) = (guard.row as isize + drow, guard.col as isize + dcol);
+                let blocked = matrix.is_blocked(row, col)?;
+                let (row, col) = (row as usize, col as usize);
+                Some((row, col, blocked || overlay == Some((row, col))))
+            })
+            .collect::<Vec<_>>();
+        let mut wanted = HashMap::<(usize, usize), usize>::new();
+        for (row, col, _) in ahead.iter().flatten().filter(|(_, _, blocked)| !blocked) {
+            *wanted.entry((*row, *col)).or_default() += 1;
+        }
+
+        for ((guard, stops), ahead) in guards.iter_mut().zip(stops).zip(ahead) {
+            let Some(location) = guard else {
+                continue;
+            };
+            match ahead {
+                None => {
+                    stops.push(*location);
+                    *guard = None;
+                }
+                Some((row, col, blocked)) => {
+                    if blocked || taken.contains(&(row, col)) || wanted[&(row, col)] > 1 {
+                        stops.push(*location);
+                        location.dir = turn.apply(location.dir);
+                    } else {
+                        (location.row, location.col) = (row, col);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Moves all guards at once, one step or turn per tick, until they all leave the map,
+    /// `overlay` is an extra obstacle.
+    /// A guard doesn't step into a cell taken by another guard at the start of the tick
+    /// or wanted by another guard in the same tick, it turns as if there was an obstacle.
+    /// Guards far from each other and from obstacles skip many ticks at once.
+    ///
+    /// Returns locations where every guard turned, starting with its start and ending
+    /// either where it leaves the map or where guards get into a state they've already
+    /// been in, and whether that happened, i.e. whether they loop.
+    pub fn patrol(
+        &self,
+        matrix: &Matrix,
+        guards: &[Location],
+        overlay: Option<(usize, usize)>,
+        turn: Turn,
+    ) -> (Vec<Vec<Location>>, bool) {
+        let mut stops = guards.iter().map(|guard| vec![*guard]).collect::<Vec<_>>();
+        let mut guards = guards.iter().copied().map(Some).collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+
+        while guards.iter().any(Option::is_some) {
+            if !seen.insert(guards.clone()) {
+                for (stops, guard) in stops.iter_mut().zip(&guards) {
+                    stops.extend(*guard);
+                }
+                return (stops, true);
+            }
+
+            let ticks = self.free_ticks(&guards, overlay);
+            if ticks == 0 {
+                Self::tick(matrix, &mut guards, &mut stops, overlay, turn);
+                continue;
+            }
+            for guard in guards.iter_mut().flatten() {
+                let (drow, dcol) = guard.dir.drow_dcol();
+                guard.row = guard.row.wrapping_add_signed(drow * ticks as isize);
+                guard.col = guard.col.wrapping_add_signed(dcol * ticks as isize);
+            }
+        }
+
+        (stops, false)
+    }
+}
+
+/// Expands turn points of a route into every cell the guard walks through,
+/// each with the direction it's entered with.
+pub fn path(stops: &[Location]) -> Vec<Location> {
+    let mut out = vec![stops[0]];
+    for segment in stops.windows(2) {
+        let (from, to) = (segment[0], segment[1]);
+        let (drow, dcol) = to.dir.drow_dcol();
+        let (mut row, mut col) = (from.row, from.col);
+        while (row, col) != (to.row, to.col) {
+            row = (row as isize + drow) as usize;
+            col = (col as isize + dcol) as usize;
+            out.push(Location {
+                dir: to.dir,
+                row,
+                col,
+            });
+        }
+    }
+    out
+}
+
+/// The map as it's drawn in the puzzle text.
+#[derive(Debug)]
+pub struct Frame {
+    rows: Vec<Vec<u8>>,
+}
+
+impl Frame {
+    /// Draws paths of guards with `|`, `-` and `+` where they moved both ways,
+    /// and marks `obstacles` with `O`.
+    pub fn new(matrix: &Matrix, paths: &[Vec<Location>], obstacles: &[(usize, usize)]) -> Self {
+        let mut rows = matrix
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell {
+                        Cell::Blocked => b'#',
+                        Cell::Empty | Cell::Visited => b'.',
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut mark = |row: usize, col: usize, dir: Direction| {
+            let glyph = match dir {
+                Direction::Left | Direction::Right => b'-',
+                Direction::Up | Direction::Down => b'|',
+            };
+            let cell = &mut rows[row][col];
+            *cell = match *cell {
+                b'.' => glyph,
+                prev if prev == glyph => glyph,
+                _ => b'+',
+            };
+        };
+        for path in paths {
+            for (idx, location) in path.iter().enumerate() {
+                mark(location.row, location.col, location.dir);
+                // the guard leaves a cell in the direction it enters the next one
+                if let Some(next) = path.get(idx + 1) {
+                    mark(location.row, location.col, next.dir);
+                }
+            }
+        }
+
+        for start in paths.iter().filter_map(|path| path.first()) {
+            rows[start.row][start.col] = start.dir.glyph();
+        }
+        for (row, col) in obstacles {
+            rows[*row][*col] = b'O';
+        }
+
+        Self { rows }
+    }
+
+    /// Writes the frame as a binary PPM image, every cell is a `scale`x`scale` square.
+    pub fn write_ppm(&self, mut out: impl Write, scale: usize) -> std::io::Result<()> {
+        let height = self.rows.len();
+        let width = self.rows.first().map_or(0, |row| row.len());
+        writeln!(out, "P6 {} {} 255", width * scale, height * scale)?;
+
+        for row in &self.rows {
+            let line = row
+                .iter()
+                .flat_map(|glyph| {
+                    let rgb: [u8; 3] = match glyph {
+                        b'#' => [64, 64, 64],
+                        b'|' | b'-' | b'+' => [80, 140, 255],
+                        b'^' | b'>' | b'v' | b'<' => [40, 200, 80],
+                        b'O' => [230, 40, 40],
+                        _ => [240, 240, 240],
+                    };
+                    std::iter::repeat_n(rgb, scale).flatten()
+                })
+                .collect::<Vec<_>>();
+            for _ in 0..scale {
+                out.write_all(&line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in &self.rows {
+            writeln!(f, "{}", String::from_utf8_lossy(row))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,561 @@
+use adventofcode2024::args::{take_arg, take_flag};
+use std::{
+    fmt::{Debug, Display},
+    ops::ControlFlow,
+    str::FromStr,
+};
+
+/// Unsigned integers equations are evaluated in, every operation is checked
+/// and an overflow means that the result exceeds any total.
+pub trait Number: Copy + Ord + Debug + Display + FromStr + Into<u128> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn from_u32(n: u32) -> Self;
+    fn to_u32(self) -> Option<u32>;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn checked_rem(self, rhs: Self) -> Option<Self>;
+    fn checked_pow(self, exp: u32) -> Option<Self>;
+}
+
+macro_rules! impl_number {
+    ($($t:ty)*) => {
+        $(
+            impl Number for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn from_u32(n: u32) -> Self {
+                    Self::from(n)
+                }
+                fn to_u32(self) -> Option<u32> {
+                    u32::try_from(self).ok()
+                }
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+                fn checked_div(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_div(self, rhs)
+                }
+                fn checked_rem(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_rem(self, rhs)
+                }
+                fn checked_pow(self, exp: u32) -> Option<Self> {
+                    <$t>::checked_pow(self, exp)
+                }
+            }
+        )*
+    };
+}
+
+impl_number!(u64 u128);
+
+/// Values of `lhs` that satisfy `lhs <op> rhs == total`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Undo<N> {
+    None,
+    One(N),
+    /// Any `lhs` works, e.g. `lhs * 0 == 0`.
+    Any,
+}
+
+impl<N> From<Option<N>> for Undo<N> {
+    fn from(lhs: Option<N>) -> Self {
+        lhs.map_or(Self::None, Self::One)
+    }
+}
+
+/// A binary operator, evaluated left to right.
+/// `undo` lets the solver go from the total backwards and prune early.
+pub trait Operator<N: Number> {
+    fn symbol(&self) -> String;
+    /// `None` if the result doesn't exist or doesn't fit into `N`.
+    fn apply(&self, lhs: N, rhs: N) -> Option<N>;
+    fn undo(&self, total: N, rhs: N) -> Undo<N>;
+}
+
+pub struct Add;
+
+impl<N: Number> Operator<N> for Add {
+    fn symbol(&self) -> String {
+        String::from("+")
+    }
+    fn apply(&self, lhs: N, rhs: N) -> Option<N> {
+        lhs.checked_add(rhs)
+    }
+    fn undo(&self, total: N, rhs: N) -> Undo<N> {
+        total.checked_sub(rhs).into()
+    }
+}
+
+pub struct Sub;
+
+impl<N: Number> Operator<N> for Sub {
+    fn symbol(&self) -> String {
+        String::from("-")
+    }
+    fn apply(&self, lhs: N, rhs: N) -> Option<N> {
+        lhs.checked_sub(rhs)
+    }
+    fn undo(&self, total: N, rhs: N) -> Undo<N> {
+        total.checked_add(rhs).into()
+    }
+}
+
+pub struct Mul;
+
+impl<N: Number> Operator<N> for Mul {
+    fn symbol(&self) -> String {
+        String::from("*")
+    }
+    fn apply(&self, lhs: N, rhs: N) -> Option<N> {
+        lhs.checked_mul(rhs)
+    }
+    fn undo(&self, total: N, rhs: N) -> Undo<N> {
+        if rhs == N::ZERO {
+            return if total == N::ZERO {
+                Undo::Any
+            } else {
+                Undo::None
+            };
+        }
+        exact_div(total, rhs).into()
+    }
+}
+
+/// Division that is only allowed when there's no remainder.
+pub struct Div;
+
+impl<N: Number> Operator<N> for Div {
+    fn symbol(&self) -> String {
+        String::from("/")
+    }
+    fn apply(&self, lhs: N, rhs: N) -> Option<N> {
+        exact_div(lhs, rhs)
+    }
+    fn undo(&self, total: N, rhs: N) -> Undo<N> {
+        if rhs == N::ZERO {
+            return Undo::None;
+        }
+        total.checked_mul(rhs).into()
+    }
+}
+
+fn exact_div<N: Number>(lhs: N, rhs: N) -> Option<N> {
+    (lhs.checked_rem(rhs)? == N::ZERO).then(|| lhs.checked_div(rhs))?
+}
+
+pub struct Pow;
+
+impl<N: Number> Operator<N> for Pow {
+    fn symbol(&self) -> String {
+        String::from("^")
+    }
+    fn apply(&self, lhs: N, rhs: N) -> Option<N> {
+        // any exponent that doesn't fit into u32 overflows for lhs > 1
+        lhs.checked_pow(rhs.to_u32().unwrap_or(u32::MAX))
+    }
+    fn undo(&self, total: N, rhs: N) -> Undo<N> {
+        if rhs == N::ZERO {
+            return if total == N::ONE {
+                Undo::Any
+            } else {
+                Undo::None
+            };
+        }
+        let exp = rhs.to_u32().unwrap_or(u32::MAX);
+
+        // binary search for the integer root
+        let (mut lo, mut hi) = (N::ZERO, total);
+        while lo < hi {
+            let half = hi
+                .checked_sub(lo)
+                .and_then(|d| d.checked_div(N::from_u32(2)));
+            let mid = half.and_then(|half| lo.checked_add(half)).unwrap();
+            match mid.checked_pow(exp) {
+                Some(value) if value == total => return Undo::One(mid),
+                Some(value) if value < total => lo = mid.checked_add(N::ONE).unwrap(),
+                _ => hi = mid,
+            }
+        }
+        (lo.checked_pow(exp) == Some(total)).then_some(lo).into()
+    }
+}
+
+/// Appends digits of `rhs` written in `base` to `lhs`.
+pub struct Concat {
+    pub base: u32,
+}
+
+impl Concat {
+    /// `base^digits(rhs)`, `None` if it doesn't fit.
+    fn shift<N: Number>(&self, rhs: N) -> Option<N> {
+        let base = N::from_u32(self.base);
+        let mut shift = base;
+        let mut rest = rhs.checked_div(base)?;
+        while rest != N::ZERO {
+            shift = shift.checked_mul(base)?;
+            rest = rest.checked_div(base)?;
+        }
+        Some(shift)
+    }
+}
+
+impl<N: Number> Operator<N> for Concat {
+    fn symbol(&self) -> String {
+        match self.base {
+            10 => String::from("||"),
+            base => format!("||{base}"),
+        }
+    }
+    fn apply(&self, lhs: N, rhs: N) -> Option<N> {
+        match self.shift(rhs) {
+            Some(shift) => lhs.checked_mul(shift)?.checked_add(rhs),
+            // `lhs` has to be zero for `lhs || rhs` to fit
+            None => (lhs == N::ZERO).then_some(rhs),
+        }
+    }
+    fn undo(&self, total: N, rhs: N) -> Undo<N> {
+        match self.shift(rhs) {
+            Some(shift) => total
+                .checked_rem(shift)
+                .filter(|rest| *rest == rhs)
+                .and_then(|_| total.checked_div(shift))
+                .into(),
+            None => (total == rhs).then_some(N::ZERO).into(),
+        }
+    }
+}
+
+pub type Operators<N> = Vec<Box<dyn Operator<N>>>;
+
+/// Parses space separated operators: `+ - * / ^ ||` and `||N` for concatenation in base N.
+pub fn parse_operators<N: Number>(s: &str) -> Operators<N> {
+    s.split_whitespace()
+        .map(|op| -> Box<dyn Operator<N>> {
+            match op {
+                "+" => Box::new(Add),
+                "-" => Box::new(Sub),
+                "*" => Box::new(Mul),
+                "/" => Box::new(Div),
+                "^" => Box::new(Pow),
+                "||" => Box::new(Concat { base: 10 }),
+                _ => match op.strip_prefix("||").map(str::parse::<u32>) {
+                    Some(Ok(base)) if base >= 2 => Box::new(Concat { base }),
+                    _ => panic!("unknown operator {op:?}"),
+                },
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+pub struct Equation<N> {
+    pub total: N,
+    numbers: Vec<N>,
+}
+
+fn parse_number<N: Number>(s: &str) -> N {
+    s.parse()
+        .unwrap_or_else(|_| panic!("{s:?} is not a number or is too large"))
+}
+
+impl<N: Number> Equation<N> {
+    pub fn parse(line: &str) -> Self {
+        let (total, rest) = line.split_once(": ").unwrap();
+        let total = parse_number(total);
+        let numbers = rest.split(' ').map(parse_number).collect::<Vec<_>>();
+
+        Self { numbers, total }
+    }
+
+    /// Evaluates left to right, `assignment` has an index in `operators` for every gap.
+    /// `None` if an intermediate result doesn't exist or overflows.
+    pub fn evaluate(&self, operators: &[Box<dyn Operator<N>>], assignment: &[usize]) -> Option<N> {
+        assert_eq!(assignment.len(), self.numbers.len() - 1);
+        assignment
+            .iter()
+            .zip(&self.numbers[1..])
+            .try_fold(self.numbers[0], |lhs, (op, rhs)| {
+                operators[*op].apply(lhs, *rhs)
+            })
+    }
+
+    /// Formats a solution as `3267 = 81 * 40 + 27`.
+    pub fn expression(&self, operators: &[Box<dyn Operator<N>>], assignment: &[usize]) -> String {
+        let mut out = format!("{} = {}", self.total, self.numbers[0]);
+        for (op, rhs) in assignment.iter().zip(&self.numbers[1..]) {
+            out.push_str(&format!(" {} {rhs}", operators[*op].symbol()));
+        }
+        out
+    }
+
+    /// Returns the first operator assignment that makes the equation true.
+    pub fn solve(&self, operators: &[Box<dyn Operator<N>>]) -> Option<Vec<usize>> {
+        let mut assignment = vec![0; self.numbers.len() - 1];
+        let last = self.numbers.len() - 1;
+        match self.backward(last, self.total, operators, &mut assignment, &mut |found| {
+            ControlFlow::Break(found.to_vec())
+        }) {
+            ControlFlow::Break(found) => Some(found),
+            ControlFlow::Continue(()) => None,
+        }
+    }
+
+    /// Returns the number of operator assignments that make the equation true.
+    pub fn count(&self, operators: &[Box<dyn Operator<N>>]) -> u64 {
+        let mut assignment = vec![0; self.numbers.len() - 1];
+        let last = self.numbers.len() - 1;
+        let mut count = 0;
+        let _ = self.backward(last, self.total, operators, &mut assignment, &mut |_| {
+            count += 1;
+            ControlFlow::<()>::Continue(())
+        });
+        count
+    }
+
+    /// Goes from the last number to the first one, undoing operators,
+    /// so branches that can't produce the total are cut early.
+    fn backward<B>(
+        &self,
+        last: usize,
+        total: N,
+        operators: &[Box<dyn Operator<N>>],
+        assignment: &mut [usize],
+        f: &mut dyn FnMut(&[usize]) -> ControlFlow<B>,
+    ) -> ControlFlow<B> {
+        if last == 0 {
+            if self.numbers[0] == total {
+                return f(assignment);
+            }
+            return ControlFlow::Continue(());
+        }
+        for (idx, op) in operators.iter().enumerate() {
+            assignment[last - 1] = idx;
+            match op.undo(total, self.numbers[last]) {
+                Undo::None => {}
+                Undo::One(lhs) => self.backward(last - 1, lhs, operators, assignment, f)?,
+                Undo::Any => {
+                    let first = self.numbers[0];
+                    self.forward(1, last, first, operators, assignment, f)?
+                }
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Tries every assignment of `numbers[..end]` that can be evaluated,
+    /// used when any value of the prefix satisfies the rest.
+    fn forward<B>(
+        &self,
+        next: usize,
+        end: usize,
+        value: N,
+        operators: &[Box<dyn Operator<N>>],
+        assignment: &mut [usize],
+        f: &mut dyn FnMut(&[usize]) -> ControlFlow<B>,
+    ) -> ControlFlow<B> {
+        if next == end {
+            return f(assignment);
+        }
+        for (idx, op) in operators.iter().enumerate() {
+            if let Some(value) = op.apply(value, self.numbers[next]) {
+                assignment[next - 1] = idx;
+                self.forward(next + 1, end, value, operators, assignment, f)?;
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// Sums totals of equations that can be made true with `operators`.
+/// Numbers are parsed as `N`, the sum is always `u128`.
+pub fn solve<N: Number>(input: &str, operators: &[Box<dyn Operator<N>>]) -> u128 {
+    let mut out = 0_u128;
+    for equation in input.trim().lines().map(Equation::<N>::parse) {
+        if let Some(solution) = equation.solve(operators) {
+            debug_assert_eq!(
+                equation.evaluate(operators, &solution),
+                Some(equation.total)
+            );
+            out = out
+                .checked_add(equation.total.into())
+                .expect("sum of totals overflows u128");
+        }
+    }
+    out
+}
+
+/// Command line shared by both parts:
+/// `[--u128] [--ops "<operators>"] [--explain | --count]`.
+pub fn run(input: &str, default_operators: &str) {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let u128_mode = take_flag(&mut args, "--u128");
+    let operators = take_arg(&mut args, "--ops").unwrap_or_else(|| String::from(default_operators));
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    if u128_mode {
+        print_output::<u128>(input, &operators, &args)
+    } else {
+        print_output::<u64>(input, &operators, &args)
+    }
+}
+
+fn print_output<N: Number>(input: &str, operators: &str, args: &[&str]) {
+    let operators = parse_operators::<N>(operators);
+    match args {
+        [] => println!("{}", solve::<N>(input, &operators)),
+        ["--explain"] => {
+            for equation in input.trim().lines().map(Equation::<N>::parse) {
+                if let Some(solution) = equation.solve(&operators) {
+                    println!("{}", equation.expression(&operators, &solution));
+                }
+            }
+            println!("{}", solve::<N>(input, &operators));
+        }
+        ["--count"] => {
+            let mut total = 0;
+            for line in input.trim().lines() {
+                let count = Equation::<N>::parse(line).count(&operators);
+                println!("{line}: {count}");
+                total += count;
+            }
+            println!("{total}");
+        }
+        _ => panic!("usage: [--u128] [--ops \"<operators>\"] [--explain | --count]"),
+    }
+}
+
+#[cfg(test)]
+fn solution_of<N: Number>(line: &str, operators: &str) -> Option<String> {
+    let operators = parse_operators::<N>(operators);
+    let equation = Equation::<N>::parse(line);
+    equation
+        .solve(&operators)
+        .map(|solution| equation.expression(&operators, &solution))
+}
+
+#[cfg(test)]
+fn evaluate_with<N: Number>(line: &str, operator: &str) -> Option<N> {
+    let equation = Equation::<N>::parse(line);
+    let gaps = equation.numbers.len() - 1;
+    equation.evaluate(&parse_operators(operator), &vec![0; gaps])
+}
+
+#[test]
+fn test_solve() {
+    let solution = |line| solution_of::<u64>(line, "+ *");
+    assert_eq!(solution("3267: 81 40 27").unwrap(), "3267 = 81 * 40 + 27");
+    assert_eq!(solution("7290: 6 8 6 15"), None);
+    assert_eq!(
+        solution_of::<u64>("7290: 6 8 6 15", "+ * ||").unwrap(),
+        "7290 = 6 * 8 || 6 * 15"
+    );
+    assert_eq!(
+        solution_of::<u64>("1000: 10 0 0", "||").unwrap(),
+        "1000 = 10 || 0 || 0"
+    );
+    // the first number can't be made zero, but it's multiplied by zero anyway
+    assert_eq!(solution("0: 5 0").unwrap(), "0 = 5 * 0");
+
+    let equation = "123456789123456789: 1 2 3 4 5 6 7 8 9 1 2 3 4 5 6 7 8 9";
+    assert_eq!(
+        solution_of::<u64>(equation, "+ * ||").unwrap(),
+        format!(
+            "{} = {}",
+            &equation[..18],
+            equation[20..].replace(' ', " || ")
+        )
+    );
+}
+
+#[test]
+fn test_operators() {
+    let operators = parse_operators::<u64>("+ * ||");
+    let count = |line| Equation::<u64>::parse(line).count(&operators);
+    assert_eq!(count("3267: 81 40 27"), 2);
+    assert_eq!(count("292: 11 6 16 20"), 1);
+    assert_eq!(count("83: 17 5"), 0);
+    // both `5 * 0 + 0` and `5 + 0 * 0` for any first operator, and `5 * 0 * 0`, `5 * 0 || 0`
+    assert_eq!(count("0: 5 0 0"), 5);
+
+    assert_eq!(
+        solution_of::<u64>("5: 20 15", "- /").unwrap(),
+        "5 = 20 - 15"
+    );
+    assert_eq!(solution_of::<u64>("4: 20 5", "- /").unwrap(), "4 = 20 / 5");
+    assert_eq!(solution_of::<u64>("3: 20 6", "- /"), None);
+    assert_eq!(solution_of::<u64>("1: 20 25", "-"), None);
+    assert_eq!(solution_of::<u64>("256: 2 8", "^").unwrap(), "256 = 2 ^ 8");
+    assert_eq!(
+        solution_of::<u64>("81: 3 2 2", "^").unwrap(),
+        "81 = 3 ^ 2 ^ 2"
+    );
+    assert_eq!(solution_of::<u64>("1: 7 0", "^").unwrap(), "1 = 7 ^ 0");
+    assert_eq!(solution_of::<u64>("255: 7 0", "^"), None);
+    // 0b101 || 0b11 = 0b10111
+    assert_eq!(
+        solution_of::<u64>("23: 5 3", "||2").unwrap(),
+        "23 = 5 ||2 3"
+    );
+    assert_eq!(solution_of::<u64>("53: 5 3", "||2"), None);
+}
+
+#[test]
+fn test_overflow() {
+    let max = u64::MAX;
+    let line = format!("{max}: {} 1", max - 1);
+    assert_eq!(
+        solution_of::<u64>(&line, "* +").unwrap(),
+        format!("{max} = {} + 1", max - 1)
+    );
+    assert_eq!(evaluate_with::<u64>(&line, "||"), None);
+
+    // 2^32 * 2^32 doesn't fit, neither does 2^32 || 2^32
+    let line = format!("{max}: 4294967296 4294967296");
+    assert_eq!(evaluate_with::<u64>(&line, "*"), None);
+    assert_eq!(evaluate_with::<u64>(&line, "||"), None);
+    assert_eq!(evaluate_with::<u64>(&line, "^"), None);
+    assert_eq!(solution_of::<u64>(&line, "+ * || ^"), None);
+
+    // 10^20 doesn't fit into u64, so a 20 digit number can only be appended to zero
+    let line = format!("{max}: 0 {max}");
+    assert!(solution_of::<u64>(&line, "||").is_some());
+    assert_eq!(evaluate_with::<u64>(&line, "||"), Some(max));
+    let line = format!("{max}: 1 {max}");
+    assert_eq!(solution_of::<u64>(&line, "||"), None);
+    assert_eq!(evaluate_with::<u64>(&line, "||"), None);
+    assert_eq!(solution_of::<u64>(&format!("1: {max} 1"), "+ * ||"), None);
+    assert_eq!(solution_of::<u64>(&format!("{max}: {max} 1"), "-"), None);
+    assert_eq!(solution_of::<u64>(&format!("1: {max} 2"), "/ ^"), None);
+
+    // totals of u64 equations are summed without overflowing
+    let input = format!("{max}: {max}\n{max}: {} 1\n", max - 1);
+    assert_eq!(
+        solve::<u64>(&input, &parse_operators("* +")),
+        2 * u128::from(max)
+    );
+
+    // (2^64 + 1) * (2^64 - 1) = 2^128 - 1
+    let input = format!("{}: 18446744073709551617 {max}", u128::MAX);
+    assert_eq!(solve::<u128>(&input, &parse_operators("* + ||")), u128::MAX);
+    assert_eq!(
+        evaluate_with::<u128>(&input, "+"),
+        Some(18446744073709551617 + u128::from(max))
+    );
+}
+
+#[test]
+#[should_panic(expected = "is not a number or is too large")]
+fn test_too_large() {
+    Equation::<u64>::parse("18446744073709551616: 1");
+}
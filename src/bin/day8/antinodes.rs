@@ -0,0 +1,190 @@
+use adventofcode2024::args::take_arg;
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Position {
+    pub row: isize,
+    pub col: isize,
+}
+
+impl Position {
+    fn add(self, drow: isize, dcol: isize) -> Position {
+        Position {
+            row: self.row + drow,
+            col: self.col + dcol,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Matrix {
+    pub rows_count: usize,
+    pub cols_count: usize,
+    pub clusters: BTreeMap<u8, Vec<Position>>,
+}
+
+impl Matrix {
+    pub fn parse(input: &str) -> Self {
+        let mut rows_count = 0;
+        let mut cols_count = 0;
+        let mut clusters: BTreeMap<u8, Vec<Position>> = BTreeMap::new();
+
+        for (row, line) in input.trim().lines().enumerate() {
+            for (col, b) in line.bytes().enumerate() {
+                rows_count = std::cmp::max(rows_count, row);
+                cols_count = std::cmp::max(cols_count, col);
+
+                if b.is_ascii_alphanumeric() {
+                    let pos = Position {
+                        row: row as isize,
+                        col: col as isize,
+                    };
+                    clusters.entry(b).or_default().push(pos);
+                }
+            }
+        }
+
+        Self {
+            rows_count: rows_count + 1,
+            cols_count: cols_count + 1,
+            clusters,
+        }
+    }
+
+    fn contains(&self, pos: Position) -> bool {
+        (0..self.rows_count as isize).contains(&pos.row)
+            && (0..self.cols_count as isize).contains(&pos.col)
+    }
+
+    /// Range of `k` such that `base + k * step` stays on the grid, `step` can't be zero.
+    fn steps_within(&self, base: Position, (drow, dcol): (isize, isize)) -> (isize, isize) {
+        let (row_lo, row_hi) = axis_steps(base.row, drow, self.rows_count as isize);
+        let (col_lo, col_hi) = axis_steps(base.col, dcol, self.cols_count as isize);
+        (row_lo.max(col_lo), row_hi.min(col_hi))
+    }
+}
+
+/// Inclusive range of `k` such that `0 <= pos + k * step < len`.
+fn axis_steps(pos: isize, step: isize, len: isize) -> (isize, isize) {
+    if step == 0 {
+        return if (0..len).contains(&pos) {
+            (isize::MIN, isize::MAX)
+        } else {
+            (1, 0)
+        };
+    }
+    let floor_div = |a: isize, b: isize| a.div_euclid(b);
+    let ceil_div = |a: isize, b: isize| -(-a).div_euclid(b);
+    if step > 0 {
+        (ceil_div(-pos, step), floor_div(len - 1 - pos, step))
+    } else {
+        (ceil_div(pos - (len - 1), -step), floor_div(pos, -step))
+    }
+}
+
+fn gcd(a: isize, b: isize) -> isize {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Which points in line with a pair of same-frequency antennas are antinodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Harmonics {
+    /// Points past either antenna where the other one is exactly twice as far.
+    Double,
+    /// Same as `Double`, plus the points between antennas
+    /// where one is twice as far, when the distance splits in three.
+    Trisection,
+    /// Every whole multiple of the distance between antennas, antennas included.
+    Multiples,
+    /// Every grid point on the line through both antennas.
+    Lattice,
+}
+
+impl Harmonics {
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "double" => Self::Double,
+            "trisection" => Self::Trisection,
+            "multiples" => Self::Multiples,
+            "lattice" => Self::Lattice,
+            _ => {
+                panic!("unknown harmonics {s:?}, expected double, trisection, multiples or lattice")
+            }
+        }
+    }
+
+    /// Removes `--harmonics <harmonics>` from command line arguments.
+    pub fn take_arg(args: &mut Vec<String>, default: Self) -> Self {
+        take_arg(args, "--harmonics").map_or(default, |harmonics| Self::parse(&harmonics))
+    }
+}
+
+pub fn antinodes(matrix: &Matrix, harmonics: Harmonics) -> HashSet<Position> {
+    let mut out = HashSet::new();
+
+    for cluster in matrix.clusters.values() {
+        for (idx, a) in cluster.iter().enumerate() {
+            for b in &cluster[idx + 1..] {
+                let (drow, dcol) = (b.row - a.row, b.col - a.col);
+
+                let step = match harmonics {
+                    Harmonics::Double | Harmonics::Trisection => {
+                        let mut points = vec![a.add(-drow, -dcol), b.add(drow, dcol)];
+                        if harmonics == Harmonics::Trisection && drow % 3 == 0 && dcol % 3 == 0 {
+                            points.push(a.add(drow / 3, dcol / 3));
+                            points.push(a.add(drow / 3 * 2, dcol / 3 * 2));
+                        }
+                        out.extend(points.into_iter().filter(|p| matrix.contains(*p)));
+                        continue;
+                    }
+                    Harmonics::Multiples => (drow, dcol),
+                    Harmonics::Lattice => {
+                        let g = gcd(drow, dcol);
+                        (drow / g, dcol / g)
+                    }
+                };
+
+                let (lo, hi) = matrix.steps_within(*a, step);
+                out.extend((lo..=hi).map(|k| a.add(k * step.0, k * step.1)));
+            }
+        }
+    }
+
+    out
+}
+
+#[test]
+fn test_antinodes() {
+    let count = |input: &str, harmonics| antinodes(&Matrix::parse(input), harmonics).len();
+    let input = include_str!("input_test.txt");
+    assert_eq!(count(input, Harmonics::Double), 14);
+    assert_eq!(count(input, Harmonics::Trisection), 14);
+    assert_eq!(count(input, Harmonics::Multiples), 34);
+    assert_eq!(count(input, Harmonics::Lattice), 34);
+
+    // the delta between antennas isn't primitive
+    let input = "a......\n.......\n.......\n...a...\n.......\n.......\n.......\n";
+    let diagonal = |ks: &[isize]| {
+        ks.iter()
+            .map(|k| Position { row: *k, col: *k })
+            .collect::<HashSet<_>>()
+    };
+    let matrix = Matrix::parse(input);
+    assert_eq!(antinodes(&matrix, Harmonics::Double), diagonal(&[6]));
+    assert_eq!(
+        antinodes(&matrix, Harmonics::Trisection),
+        diagonal(&[1, 2, 6])
+    );
+    assert_eq!(
+        antinodes(&matrix, Harmonics::Multiples),
+        diagonal(&[0, 3, 6])
+    );
+    assert_eq!(
+        antinodes(&matrix, Harmonics::Lattice),
+        diagonal(&[0, 1, 2, 3, 4, 5, 6])
+    );
+}
@@ -97,7 +97,7 @@ impl std::fmt::Display for Filesystem {
     }
 }
 
-fn solve(input: &str) -> usize {
+pub fn solve(input: &str) -> usize {
     let mut fs = Filesystem::parse(input);
 
     fs.defragment();
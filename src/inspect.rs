@@ -0,0 +1,81 @@
+/// Summary of a puzzle input and results of the sanity checks
+/// that a solver relies on.
+#[derive(Debug, Default)]
+pub struct Report {
+    lines: Vec<Line>,
+}
+
+#[derive(Debug)]
+enum Line {
+    Fact {
+        name: String,
+        value: String,
+    },
+    Check {
+        name: String,
+        failure: Option<String>,
+    },
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn fact(&mut self, name: &str, value: impl std::fmt::Display) {
+        self.lines.push(Line::Fact {
+            name: name.to_string(),
+            value: value.to_string(),
+        });
+    }
+
+    /// Records a check that passes if there's no `failure`.
+    pub fn check(&mut self, name: &str, failure: Option<String>) {
+        self.lines.push(Line::Check {
+            name: name.to_string(),
+            failure,
+        });
+    }
+
+    pub fn passed(&self) -> bool {
+        self.lines.iter().all(|line| match line {
+            Line::Fact { .. } => true,
+            Line::Check { failure, .. } => failure.is_none(),
+        })
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            match line {
+                Line::Fact { name, value } => writeln!(f, "{name}: {value}")?,
+                Line::Check {
+                    name,
+                    failure: None,
+                } => writeln!(f, "[ok]   {name}")?,
+                Line::Check {
+                    name,
+                    failure: Some(failure),
+                } => writeln!(f, "[FAIL] {name}: {failure}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test() {
+    let mut report = Report::new();
+    report.fact("rows", 10);
+    report.check("grid is square", None);
+    assert!(report.passed());
+
+    report.check("grid is tiny", Some(String::from("10 rows")));
+    assert!(!report.passed());
+
+    assert_eq!(
+        report.to_string(),
+        "rows: 10\n[ok]   grid is square\n[FAIL] grid is tiny: 10 rows\n"
+    );
+}
@@ -0,0 +1,4 @@
+pub mod allocator;
+pub mod args;
+pub mod inspect;
+pub mod repl;
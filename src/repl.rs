@@ -0,0 +1,95 @@
+use std::io::{BufRead, Write};
+
+/// Parsed puzzle state of a single day that can be explored command by command.
+pub trait Repl {
+    /// `(usage, description)` pairs, the first word of usage is the command name.
+    fn commands(&self) -> &'static [(&'static str, &'static str)];
+
+    /// Executes one of the `commands`, returns the text to print.
+    fn exec(&mut self, command: &str, args: &[&str]) -> Result<String, String>;
+}
+
+pub fn run(
+    repl: &mut dyn Repl,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> std::io::Result<()> {
+    write!(output, "> ")?;
+    output.flush()?;
+
+    for line in input.lines() {
+        let line = line?;
+        let mut words = line.split_whitespace();
+
+        match words.next() {
+            None => {}
+            Some("quit" | "exit") => break,
+            Some("help") => {
+                let builtin = [("help", "print this message"), ("quit", "exit the repl")];
+                for (usage, description) in repl.commands().iter().chain(&builtin) {
+                    writeln!(output, "{usage:<28} {description}")?;
+                }
+            }
+            Some(command) => {
+                let known = repl
+                    .commands()
+                    .iter()
+                    .any(|(usage, _)| usage.split_whitespace().next() == Some(command));
+
+                if !known {
+                    writeln!(output, "unknown command {command:?}, try \"help\"")?;
+                } else {
+                    let args = words.collect::<Vec<_>>();
+                    match repl.exec(command, &args) {
+                        Ok(out) => writeln!(output, "{}", out.trim_end())?,
+                        Err(err) => writeln!(output, "error: {err}")?,
+                    }
+                }
+            }
+        }
+
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test() {
+    struct Counter(usize);
+
+    impl Repl for Counter {
+        fn commands(&self) -> &'static [(&'static str, &'static str)] {
+            &[
+                ("inc [n]", "increment the counter"),
+                ("get", "print the counter"),
+            ]
+        }
+
+        fn exec(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+            match command {
+                "inc" => {
+                    let n = match args {
+                        [] => 1,
+                        [n] => n.parse().map_err(|_| format!("bad number {n:?}"))?,
+                        _ => return Err(String::from("too many arguments")),
+                    };
+                    self.0 += n;
+                    Ok(String::new())
+                }
+                "get" => Ok(self.0.to_string()),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    let mut output = vec![];
+    let input = "inc\ninc 5\nget\ninc x\nfoo\n\nquit\nget\n";
+    run(&mut Counter(0), input.as_bytes(), &mut output).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "> \n> \n> 6\n> error: bad number \"x\"\n> unknown command \"foo\", try \"help\"\n> > "
+    );
+}
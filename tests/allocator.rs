@@ -0,0 +1,22 @@
+//! Counters are process-wide, so this lives in its own test binary
+//! where no other test allocates concurrently.
+
+use adventofcode2024::allocator::Counting;
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+#[test]
+fn test() {
+    let baseline = Counting::reset();
+    let mut v = Vec::<u64>::with_capacity(1024);
+    v.push(1);
+    drop(v);
+    let v = vec![0_u8; 100];
+    let stats = baseline.stats();
+    drop(v);
+
+    assert!(stats.peak_bytes >= 8 * 1024);
+    assert!(stats.total_bytes >= 8 * 1024 + 100);
+    assert!(stats.allocations >= 2);
+}